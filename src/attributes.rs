@@ -0,0 +1,141 @@
+//! The safety levels and remarks a function's `attributes` may use, after
+//! the ATTRIBUTES section of the POSIX and glibc manuals:
+//!
+//! ```yaml
+//! attributes:
+//!   thread_safety:
+//!     level: MT-Unsafe
+//!     reasons: [race:tmbuf, env, locale]
+//!   async_signal_safety:
+//!     level: AS-Unsafe
+//!     reasons: [heap, lock]
+//!   async_cancel_safety:
+//!     level: AC-Unsafe
+//!     reasons: [lock, mem]
+//!   cancellation_point: true
+//! ```
+//!
+//! Levels and remarks match case-insensitively. A remark may name what it
+//! applies to after a colon (`race:tmbuf`, `sig:SIGALRM`).
+
+use crate::definitions::attributes::{Attributes, Safety};
+
+pub(crate) const AS_UNSAFE: &str = "AS-Unsafe";
+
+const MT_LEVELS: &[&str] = &["MT-Safe", "MT-Unsafe"];
+const AS_LEVELS: &[&str] = &["AS-Safe", AS_UNSAFE];
+const AC_LEVELS: &[&str] = &["AC-Safe", "AC-Unsafe"];
+
+const REMARKS: &[&str] = &[
+    "const", "corrupt", "cwd", "dlopen", "env", "fd", "heap", "hostid", "i18n", "init", "locale",
+    "lock", "mem", "plugin", "race", "sig", "sigintr", "term", "timer",
+];
+
+/// Canonicalises every level and remark. Fails on the first unknown one.
+pub(crate) fn normalize(attributes: Attributes) -> Result<Attributes, String> {
+    Ok(Attributes {
+        thread_safety: normalize_safety(attributes.thread_safety, MT_LEVELS, "thread safety")?,
+        async_signal_safety: normalize_safety(
+            attributes.async_signal_safety,
+            AS_LEVELS,
+            "async-signal safety",
+        )?,
+        async_cancel_safety: normalize_safety(
+            attributes.async_cancel_safety,
+            AC_LEVELS,
+            "async-cancel safety",
+        )?,
+        cancellation_point: attributes.cancellation_point,
+    })
+}
+
+fn normalize_safety(
+    safety: Option<Safety>,
+    levels: &[&str],
+    what: &str,
+) -> Result<Option<Safety>, String> {
+    let safety = match safety {
+        Some(safety) => safety,
+        None => return Ok(None),
+    };
+
+    let level = levels
+        .iter()
+        .find(|level| level.eq_ignore_ascii_case(safety.level.trim()))
+        .ok_or_else(|| format!("unknown {} \"{}\"", what, safety.level))?;
+
+    let reasons = safety
+        .reasons
+        .iter()
+        .map(|reason| {
+            let (remark, target) = match reason.trim().split_once(':') {
+                Some((remark, target)) => (remark, Some(target)),
+                None => (reason.trim(), None),
+            };
+
+            let remark = REMARKS
+                .iter()
+                .find(|known| known.eq_ignore_ascii_case(remark))
+                .ok_or_else(|| format!("unknown safety remark \"{}\"", reason))?;
+
+            Ok(match target {
+                Some(target) => format!("{}:{}", remark, target),
+                None => remark.to_string(),
+            })
+        })
+        .collect::<Result<Vec<String>, String>>()?;
+
+    Ok(Some(Safety {
+        level: level.to_string(),
+        reasons,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn safety(level: &str, reasons: &[&str]) -> Option<Safety> {
+        Some(Safety {
+            level: level.to_string(),
+            reasons: reasons.iter().map(|r| r.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn normalize_canonicalises_levels_and_remarks() {
+        let attributes = normalize(Attributes {
+            thread_safety: safety("mt-unsafe", &["RACE:tmbuf", " env "]),
+            async_signal_safety: safety("AS-UNSAFE", &["heap"]),
+            async_cancel_safety: None,
+            cancellation_point: Some(true),
+        })
+        .unwrap();
+
+        let thread_safety = attributes.thread_safety.unwrap();
+        assert_eq!(thread_safety.level, "MT-Unsafe");
+        assert_eq!(thread_safety.reasons, vec!["race:tmbuf", "env"]);
+        assert_eq!(attributes.async_signal_safety.unwrap().level, AS_UNSAFE);
+        assert!(attributes.async_cancel_safety.is_none());
+        assert_eq!(attributes.cancellation_point, Some(true));
+    }
+
+    #[test]
+    fn normalize_rejects_unknown_levels_and_remarks() {
+        let attributes = |thread_safety| Attributes {
+            thread_safety,
+            async_signal_safety: None,
+            async_cancel_safety: None,
+            cancellation_point: None,
+        };
+
+        assert_eq!(
+            normalize(attributes(safety("AS-Safe", &[]))).err(),
+            Some(String::from("unknown thread safety \"AS-Safe\""))
+        );
+        assert_eq!(
+            normalize(attributes(safety("MT-Safe", &["racy:x"]))).err(),
+            Some(String::from("unknown safety remark \"racy:x\""))
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Per-platform `availability` entries and the `--available` build filter.
+//!
+//! ```yaml
+//! availability:
+//!   - platform: glibc
+//!     introduced: "2.27"
+//!   - platform: macos
+//!     introduced: "10.12"
+//!     removed: "14.0"
+//! ```
+//!
+//! Versions are dot-separated numbers. They have to be quoted, or YAML
+//! reads `2.30` as the float `2.3`.
+
+use crate::{definitions::availability::Availability, vocabulary::Vocabulary};
+use std::{cmp::Ordering, fmt};
+
+fn parse_version(version: &str) -> Result<Vec<u64>, String> {
+    version
+        .trim()
+        .split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<Result<Vec<u64>, _>>()
+        .map_err(|_| format!("invalid version \"{}\"", version))
+}
+
+/// Orders versions component by component, so `2.9 < 2.27` and
+/// `2.28 == 2.28.0`.
+fn compare_versions(a: &[u64], b: &[u64]) -> Ordering {
+    for i in 0..a.len().max(b.len()) {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Maps every platform to its canonical name and checks the versions: each
+/// platform may appear once, and a `removed` version must come after
+/// `introduced`. Entries are returned in canonical platform order.
+pub(crate) fn normalize(
+    availability: &[Availability],
+    platforms: &Vocabulary,
+) -> Result<Vec<Availability>, String> {
+    let mut normalized: Vec<Availability> = Vec::new();
+
+    for entry in availability.iter() {
+        let platform = platforms
+            .canonical(&entry.platform)
+            .ok_or_else(|| format!("unknown {} \"{}\"", platforms.noun, entry.platform))?;
+
+        if normalized.iter().any(|n| n.platform == platform) {
+            return Err(format!("{} is listed more than once", platform));
+        }
+
+        let introduced = parse_version(&entry.introduced)?;
+
+        if let Some(removed) = &entry.removed {
+            if compare_versions(&parse_version(removed)?, &introduced) != Ordering::Greater {
+                return Err(format!(
+                    "{} is removed in {}, which isn't after {}",
+                    platform, removed, entry.introduced
+                ));
+            }
+        }
+
+        normalized.push(Availability {
+            platform: platform.to_string(),
+            introduced: entry.introduced.trim().to_string(),
+            removed: entry.removed.as_ref().map(|r| r.trim().to_string()),
+        });
+    }
+
+    normalized.sort_by_key(|entry| {
+        platforms
+            .names()
+            .iter()
+            .position(|name| *name == entry.platform)
+    });
+
+    Ok(normalized)
+}
+
+/// One `--available <platform>=<version>` argument.
+pub(crate) struct AvailabilityFilter {
+    platform: String,
+    version: String,
+    parsed: Vec<u64>,
+}
+
+impl AvailabilityFilter {
+    pub fn parse(argument: &str, platforms: &Vocabulary) -> Result<AvailabilityFilter, String> {
+        let (platform, version) = argument
+            .split_once('=')
+            .ok_or_else(|| format!("expected <platform>=<version>, got \"{}\"", argument))?;
+        let platform = platforms
+            .canonical(platform)
+            .ok_or_else(|| format!("unknown {} \"{}\"", platforms.noun, platform))?;
+
+        Ok(AvailabilityFilter {
+            platform: platform.to_string(),
+            version: version.trim().to_string(),
+            parsed: parse_version(version)?,
+        })
+    }
+
+    /// Whether a definition with these (normalised) entries can be used on
+    /// the filtered version. Definitions without an entry for the platform
+    /// are taken to be available, as nothing says otherwise.
+    pub fn matches(&self, availability: &[Availability]) -> bool {
+        let entry = match availability.iter().find(|a| a.platform == self.platform) {
+            Some(entry) => entry,
+            None => return true,
+        };
+        // Normalised entries always parse.
+        let version = |version: &str| parse_version(version).unwrap_or_default();
+
+        compare_versions(&version(&entry.introduced), &self.parsed) != Ordering::Greater
+            && entry.removed.as_ref().is_none_or(|removed| {
+                compare_versions(&version(removed), &self.parsed) == Ordering::Greater
+            })
+    }
+}
+
+impl fmt::Display for AvailabilityFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.platform, self.version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(platform: &str, introduced: &str, removed: Option<&str>) -> Availability {
+        Availability {
+            platform: platform.to_string(),
+            introduced: introduced.to_string(),
+            removed: removed.map(String::from),
+        }
+    }
+
+    fn platforms() -> Vocabulary {
+        Vocabulary::load_platform("/nonexistent")
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        let compare = |a: &str, b: &str| {
+            compare_versions(&parse_version(a).unwrap(), &parse_version(b).unwrap())
+        };
+
+        assert_eq!(compare("2.9", "2.27"), Ordering::Less);
+        assert_eq!(compare("2.28", "2.28.0"), Ordering::Equal);
+        assert_eq!(compare("10.12", "10.9.5"), Ordering::Greater);
+        assert!(parse_version("2.x").is_err());
+        assert!(parse_version("").is_err());
+    }
+
+    #[test]
+    fn normalize_canonicalises_platforms_and_sorts() {
+        let normalized = normalize(
+            &[
+                entry("Darwin", " 10.12 ", Some("14.0")),
+                entry("GNU libc", "2.27", None),
+            ],
+            &platforms(),
+        )
+        .unwrap();
+        let described: Vec<(&str, &str, Option<&str>)> = normalized
+            .iter()
+            .map(|a| {
+                (
+                    a.platform.as_str(),
+                    a.introduced.as_str(),
+                    a.removed.as_deref(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            described,
+            vec![("glibc", "2.27", None), ("macos", "10.12", Some("14.0"))]
+        );
+    }
+
+    #[test]
+    fn normalize_rejects_invalid_entries() {
+        let error = |availability: &[Availability]| normalize(availability, &platforms()).err();
+
+        assert_eq!(
+            error(&[entry("plan9", "1", None)]),
+            Some(String::from("unknown platform \"plan9\""))
+        );
+        assert_eq!(
+            error(&[entry("glibc", "2.1", None), entry("gnu libc", "2.2", None)]),
+            Some(String::from("glibc is listed more than once"))
+        );
+        assert_eq!(
+            error(&[entry("glibc", "2.5", Some("2.5"))]),
+            Some(String::from(
+                "glibc is removed in 2.5, which isn't after 2.5"
+            ))
+        );
+        assert_eq!(
+            error(&[entry("glibc", "two", None)]),
+            Some(String::from("invalid version \"two\""))
+        );
+    }
+
+    #[test]
+    fn filter_checks_the_introduced_and_removed_versions() {
+        let filter = AvailabilityFilter::parse("glibc=2.28", &platforms()).unwrap();
+
+        assert!(filter.matches(&[entry("glibc", "2.27", None)]));
+        assert!(filter.matches(&[entry("glibc", "2.28", None)]));
+        assert!(!filter.matches(&[entry("glibc", "2.29", None)]));
+        assert!(!filter.matches(&[entry("glibc", "2.0", Some("2.28"))]));
+        assert!(filter.matches(&[entry("musl", "1.2", None)]));
+        assert!(filter.matches(&[]));
+        assert_eq!(filter.to_string(), "glibc=2.28");
+    }
+
+    #[test]
+    fn filter_rejects_malformed_arguments() {
+        assert!(AvailabilityFilter::parse("glibc", &platforms()).is_err());
+        assert!(AvailabilityFilter::parse("plan9=1", &platforms()).is_err());
+        assert!(AvailabilityFilter::parse("glibc=new", &platforms()).is_err());
+    }
+}
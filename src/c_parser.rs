@@ -0,0 +1,1119 @@
+//! A small, forgiving reader for C headers.
+//!
+//! This is not a C preprocessor or compiler: conditional blocks are taken on
+//! every branch (except C++-only ones), macros are recorded but never expanded
+//! and common glibc/BSD attribute macros are stripped by name. That is enough
+//! to pull the public prototypes, structs, enums, typedefs and macros out of
+//! system headers for comparison against the corpus.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Default)]
+pub(crate) struct CHeader {
+    pub includes: Vec<String>,
+    pub macros: Vec<CMacro>,
+    pub functions: Vec<CFunction>,
+    pub structs: Vec<CStruct>,
+    pub enums: Vec<CEnum>,
+    pub typedefs: Vec<CTypedef>,
+}
+
+pub(crate) struct CMacro {
+    pub name: String,
+    /// Parameter names of function-like macros; `None` for object-like ones.
+    pub parameters: Option<Vec<String>>,
+}
+
+pub(crate) struct CFunction {
+    pub name: String,
+    pub returns: String,
+    /// Variadic functions end with a `...` parameter with an empty type,
+    /// matching how the corpus writes them.
+    pub parameters: Vec<CDeclaration>,
+}
+
+pub(crate) struct CStruct {
+    pub name: String,
+    pub fields: Vec<CDeclaration>,
+}
+
+pub(crate) struct CEnum {
+    pub name: String,
+    pub variants: Vec<String>,
+}
+
+pub(crate) struct CTypedef {
+    pub name: String,
+    pub _type: String,
+}
+
+/// A named (or unnamed) declarator: a parameter, field or typedef.
+pub(crate) struct CDeclaration {
+    pub name: String,
+    pub _type: String,
+}
+
+const TYPE_KEYWORDS: &[&str] = &[
+    "void", "char", "short", "int", "long", "float", "double", "signed", "unsigned", "_Bool",
+    "const", "volatile", "restrict", "struct", "union", "enum",
+];
+
+const QUALIFIERS: &[&str] = &["restrict", "__restrict", "__restrict__", "register"];
+
+impl CHeader {
+    fn merge(&mut self, other: CHeader) {
+        for _macro in other.macros {
+            if !self.macros.iter().any(|m| m.name == _macro.name) {
+                self.macros.push(_macro);
+            }
+        }
+        for function in other.functions {
+            if !self.functions.iter().any(|f| f.name == function.name) {
+                self.functions.push(function);
+            }
+        }
+        for _struct in other.structs {
+            if !self.structs.iter().any(|s| s.name == _struct.name) {
+                self.structs.push(_struct);
+            }
+        }
+        for _enum in other.enums {
+            if !self.enums.iter().any(|e| e.name == _enum.name) {
+                self.enums.push(_enum);
+            }
+        }
+        for typedef in other.typedefs {
+            if !self.typedefs.iter().any(|t| t.name == typedef.name) {
+                self.typedefs.push(typedef);
+            }
+        }
+    }
+
+    pub fn function(&self, name: &str) -> Option<&CFunction> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    pub fn _struct(&self, name: &str) -> Option<&CStruct> {
+        self.structs.iter().find(|s| s.name == name)
+    }
+
+    pub fn _enum(&self, name: &str) -> Option<&CEnum> {
+        self.enums.iter().find(|e| e.name == name)
+    }
+}
+
+pub(crate) fn parse_file(path: &Path) -> io::Result<CHeader> {
+    Ok(parse(&fs::read_to_string(path)?))
+}
+
+/// Parses `path` and every header it includes that can be found in one of
+/// `include_dirs` (or next to the including file for `"quoted"` includes).
+pub(crate) fn parse_file_with_includes(
+    path: &Path,
+    include_dirs: &[PathBuf],
+) -> io::Result<CHeader> {
+    parse_file_with_includes_except(path, include_dirs, &[])
+}
+
+/// `parse_file_with_includes`, leaving out the headers in `skip` and
+/// whatever is only reachable through them.
+pub(crate) fn parse_file_with_includes_except(
+    path: &Path,
+    include_dirs: &[PathBuf],
+    skip: &[PathBuf],
+) -> io::Result<CHeader> {
+    let mut header = parse_file(path)?;
+    let mut visited: Vec<PathBuf> = vec![path.to_path_buf()];
+    visited.extend(skip.iter().cloned());
+    let mut pending: Vec<PathBuf> = Vec::new();
+
+    queue_includes(&header, path, include_dirs, &visited, &mut pending);
+
+    while let Some(next) = pending.pop() {
+        if visited.contains(&next) {
+            continue;
+        }
+        visited.push(next.clone());
+
+        if let Ok(included) = parse_file(&next) {
+            queue_includes(&included, &next, include_dirs, &visited, &mut pending);
+            header.merge(included);
+        }
+    }
+
+    Ok(header)
+}
+
+fn queue_includes(
+    header: &CHeader,
+    path: &Path,
+    include_dirs: &[PathBuf],
+    visited: &[PathBuf],
+    pending: &mut Vec<PathBuf>,
+) {
+    for include in header.includes.iter() {
+        let local = path.parent().map(|p| p.join(include));
+        let candidates = local
+            .into_iter()
+            .chain(include_dirs.iter().map(|dir| dir.join(include)));
+
+        if let Some(found) = candidates.into_iter().find(|c| c.is_file()) {
+            if !visited.contains(&found) && !pending.contains(&found) {
+                pending.push(found);
+            }
+        }
+    }
+}
+
+pub(crate) fn parse(source: &str) -> CHeader {
+    let mut header = CHeader::default();
+    let code = preprocess(&strip_comments(source), &mut header);
+    let tokens = strip_attributes(tokenize(&code));
+
+    for declaration in split_declarations(&tokens) {
+        parse_declaration(&declaration, &mut header);
+    }
+
+    let mut deduplicated = CHeader {
+        includes: std::mem::take(&mut header.includes),
+        ..CHeader::default()
+    };
+    deduplicated.merge(header);
+    deduplicated
+}
+
+/// Tokens of a C source file with comments removed and `#include` lines
+/// dropped, for scanning which identifiers it uses. String and character
+/// literals stay single tokens.
+pub(crate) fn source_tokens(source: &str) -> Vec<String> {
+    let stripped = strip_comments(source);
+    let code: Vec<&str> = stripped
+        .lines()
+        .filter(|line| {
+            !line
+                .trim_start()
+                .strip_prefix('#')
+                .is_some_and(|directive| directive.trim_start().starts_with("include"))
+        })
+        .collect();
+
+    tokenize(&code.join("\n"))
+}
+
+/// Normalises a type string for comparison: qualifiers that don't change the
+/// type (`restrict`, `register`) are dropped and spacing is made uniform.
+pub(crate) fn normalize_type(_type: &str) -> String {
+    let tokens: Vec<String> = tokenize(_type)
+        .into_iter()
+        .filter(|t| !QUALIFIERS.contains(&t.as_str()))
+        .collect();
+
+    join_tokens(&tokens)
+}
+
+/// Finds `header_name` in the first of `include_dirs` that contains it.
+pub(crate) fn find_header(header_name: &str, include_dirs: &[PathBuf]) -> Option<PathBuf> {
+    include_dirs
+        .iter()
+        .map(|dir| dir.join(header_name))
+        .find(|path| path.is_file())
+}
+
+/// Strips the leading underscores reserved-namespace headers put on
+/// parameter names (`__stream` -> `stream`).
+pub(crate) fn public_name(name: &str) -> &str {
+    let trimmed = name.trim_start_matches('_');
+
+    if trimmed.is_empty() {
+        name
+    } else {
+        trimmed
+    }
+}
+
+fn strip_comments(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    out.push('\n');
+                }
+                i += 1;
+            }
+            i += 2;
+            out.push(' ');
+        } else if chars[i] == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if chars[i] == '"' || chars[i] == '\'' {
+            let quote = chars[i];
+            out.push(quote);
+            i += 1;
+            while i < chars.len() && chars[i] != quote && chars[i] != '\n' {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+                out.push(chars[i]);
+                i += 1;
+            }
+            if i < chars.len() {
+                out.push(chars[i]);
+                i += 1;
+            }
+        } else {
+            out.push(chars[i]);
+            i += 1;
+        }
+    }
+
+    out
+}
+
+/// Records `#define`s and `#include`s, drops every other directive and
+/// blanks out `#ifdef __cplusplus` blocks.
+fn preprocess(source: &str, header: &mut CHeader) -> String {
+    let joined = source.replace("\\\r\n", " ").replace("\\\n", " ");
+    let mut out = String::with_capacity(joined.len());
+    // One entry per open conditional: whether that block is being skipped.
+    let mut skipping: Vec<bool> = Vec::new();
+    // The name tested by an `#ifndef` on the previous line, which a
+    // following `#define` of that name turns into an include guard.
+    let mut guard: Option<String> = None;
+
+    for line in joined.lines() {
+        let trimmed = line.trim_start();
+
+        if let Some(directive) = trimmed.strip_prefix('#') {
+            let directive = directive.trim_start();
+            let (keyword, rest) = directive
+                .split_once(|c: char| c.is_whitespace())
+                .unwrap_or((directive, ""));
+            let rest = rest.trim();
+            let parent_skipping = skipping.iter().any(|s| *s);
+
+            match keyword {
+                "if" | "ifdef" | "ifndef" => {
+                    let cplusplus = rest.contains("__cplusplus") && keyword != "ifndef";
+                    skipping.push(cplusplus && !rest.starts_with('!'));
+                }
+                "elif" | "else" => {
+                    if let Some(last) = skipping.last_mut() {
+                        *last = false;
+                    }
+                }
+                "endif" => {
+                    skipping.pop();
+                }
+                "define" if !parent_skipping => parse_define(rest, guard.as_deref(), header),
+                "include" if !parent_skipping => {
+                    let target = rest.trim_matches(|c| c == '<' || c == '>' || c == '"');
+                    if !target.is_empty() && !header.includes.iter().any(|i| i == target) {
+                        header.includes.push(target.to_string());
+                    }
+                }
+                _ => {}
+            }
+
+            guard = (keyword == "ifndef").then(|| rest.to_string());
+            out.push('\n');
+            continue;
+        }
+
+        if !line.trim().is_empty() {
+            guard = None;
+        }
+
+        if !skipping.iter().any(|s| *s) {
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+fn parse_define(rest: &str, guard: Option<&str>, header: &mut CHeader) {
+    let name_end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    let name = &rest[..name_end];
+
+    if name.is_empty() || (guard == Some(name) && is_guard_value(&rest[name_end..])) {
+        return;
+    }
+
+    // Only a `(` directly after the name makes a function-like macro.
+    let parameters = rest[name_end..].strip_prefix('(').and_then(|list| {
+        list.split_once(')').map(|(list, _)| {
+            list.split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect()
+        })
+    });
+
+    if !header.macros.iter().any(|m| m.name == name) {
+        header.macros.push(CMacro {
+            name: name.to_string(),
+            parameters,
+        });
+    }
+}
+
+/// Include guards are defined bare, or as `1` the way glibc defines them.
+fn is_guard_value(value: &str) -> bool {
+    matches!(value.trim(), "" | "1")
+}
+
+fn tokenize(code: &str) -> Vec<String> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else if c == '.' && chars.get(i + 1) == Some(&'.') && chars.get(i + 2) == Some(&'.') {
+            tokens.push(String::from("..."));
+            i += 3;
+        } else if c == '"' || c == '\'' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            tokens.push(chars[start..i].iter().collect());
+        } else {
+            tokens.push(c.to_string());
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// Whether a token is one of the attribute/annotation macros system headers
+/// sprinkle around declarations.
+fn is_attribute(token: &str) -> bool {
+    const PREFIXES: &[&str] = &[
+        "__attr",
+        "__fortified",
+        "__wur",
+        "__nonnull",
+        "__asm",
+        "__glibc_",
+        "__THROW",
+        "__LEAF",
+        "__MINGW",
+        "__DARWIN",
+        "__OSX_",
+        "__IOS_",
+        "__API_",
+        "_Nullable",
+        "_Nonnull",
+        "_Null_unspecified",
+        "__dead2",
+        "__pure2",
+        "__printflike",
+        "__scanflike",
+    ];
+
+    if PREFIXES.iter().any(|p| token.starts_with(p)) {
+        return true;
+    }
+
+    matches!(
+        token,
+        "__extension__"
+            | "__inline"
+            | "__inline__"
+            | "inline"
+            | "_Noreturn"
+            | "__BEGIN_DECLS"
+            | "__END_DECLS"
+            | "_GL_ATTRIBUTE_PURE"
+    )
+}
+
+fn strip_attributes(tokens: Vec<String>) -> Vec<String> {
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i].as_str();
+
+        if token == "__NTH" {
+            // `__NTH (decl)` wraps a declaration; keep the contents.
+            if tokens.get(i + 1).map(String::as_str) == Some("(") {
+                match matching(&tokens, i + 1, "(", ")") {
+                    Some(close) => {
+                        out.extend(strip_attributes(tokens[i + 2..close].to_vec()));
+                        i = close + 1;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        if is_attribute(token) {
+            i += 1;
+            if tokens.get(i).map(String::as_str) == Some("(") {
+                match matching(&tokens, i, "(", ")") {
+                    Some(close) => i = close + 1,
+                    None => break,
+                }
+            }
+            continue;
+        }
+
+        match token {
+            "__restrict" | "__restrict__" => out.push(String::from("restrict")),
+            "__const" | "__const__" => out.push(String::from("const")),
+            "__signed__" => out.push(String::from("signed")),
+            _ => out.push(tokens[i].clone()),
+        }
+
+        i += 1;
+    }
+
+    out
+}
+
+/// Index of the token closing the group opened at `open_index`, or `None`
+/// when the group is never closed.
+pub(crate) fn matching(
+    tokens: &[String],
+    open_index: usize,
+    open: &str,
+    close: &str,
+) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, token) in tokens.iter().enumerate().skip(open_index) {
+        if token == open {
+            depth += 1;
+        } else if token == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+
+    None
+}
+
+/// Splits the token stream into top level declarations. Function bodies
+/// (inline definitions) are dropped but their prototypes are kept.
+fn split_declarations(tokens: &[String]) -> Vec<Vec<String>> {
+    let mut declarations = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i].as_str();
+
+        match token {
+            ";" => {
+                if !current.is_empty() {
+                    declarations.push(std::mem::take(&mut current));
+                }
+                i += 1;
+            }
+            "{" => {
+                // An unclosed body leaves nothing complete to declare.
+                let close = match matching(tokens, i, "{", "}") {
+                    Some(close) => close,
+                    None => break,
+                };
+
+                if current.last().map(String::as_str) == Some(")") {
+                    declarations.push(std::mem::take(&mut current));
+                } else {
+                    current.extend(tokens[i..=close].iter().cloned());
+                }
+
+                i = close + 1;
+            }
+            _ => {
+                current.push(tokens[i].clone());
+                i += 1;
+            }
+        }
+    }
+
+    declarations
+}
+
+fn parse_declaration(tokens: &[String], header: &mut CHeader) {
+    let start = tokens
+        .iter()
+        .position(|t| !matches!(t.as_str(), "extern" | "static"))
+        .unwrap_or(tokens.len());
+    let tokens = &tokens[start..];
+
+    if tokens.is_empty() || tokens[0].starts_with('"') {
+        return;
+    }
+
+    if tokens[0] == "typedef" {
+        parse_typedef(&tokens[1..], header);
+    } else if let Some(open) = tokens.iter().position(|t| t == "{") {
+        parse_tagged_body(tokens, open, None, header);
+    } else if tokens.iter().any(|t| t == "(") {
+        if let Some(function) = parse_function(tokens) {
+            header.functions.push(function);
+        }
+    }
+}
+
+/// Records the struct/union/enum whose body opens at `open`, returning the
+/// type name it can be referred to by (`struct tag`).
+fn parse_tagged_body(
+    tokens: &[String],
+    open: usize,
+    fallback_name: Option<&str>,
+    header: &mut CHeader,
+) -> Option<String> {
+    let keyword_index = tokens[..open]
+        .iter()
+        .position(|t| matches!(t.as_str(), "struct" | "union" | "enum"))?;
+    let keyword = tokens[keyword_index].as_str();
+    let tag = tokens[keyword_index + 1..open]
+        .iter()
+        .rev()
+        .find(|t| is_identifier(t))
+        .map(String::as_str);
+    let close = matching(tokens, open, "{", "}")?;
+    let body = &tokens[open + 1..close];
+    let name = tag.or(fallback_name)?.to_string();
+
+    if keyword == "enum" {
+        let variants = split_top_level(body, ",")
+            .into_iter()
+            .filter_map(|v| v.first().filter(|t| is_identifier(t)).cloned())
+            .collect();
+        header.enums.push(CEnum {
+            name: name.clone(),
+            variants,
+        });
+    } else {
+        let mut fields: Vec<CDeclaration> = Vec::new();
+        for field in split_top_level(body, ";") {
+            // Alternative `#if` branches can declare the same member twice.
+            for parsed in parse_field(&field, header) {
+                if parsed.name.is_empty() || !fields.iter().any(|f| f.name == parsed.name) {
+                    fields.push(parsed);
+                }
+            }
+        }
+        header.structs.push(CStruct {
+            name: name.clone(),
+            fields,
+        });
+    }
+
+    Some(match tag {
+        Some(tag) => format!("{} {}", keyword, tag),
+        None => format!("{} {{ ... }}", keyword),
+    })
+}
+
+fn parse_field(tokens: &[String], header: &mut CHeader) -> Vec<CDeclaration> {
+    if tokens.is_empty() {
+        return Vec::new();
+    }
+
+    // Nested struct/union members keep a collapsed type.
+    if let Some(open) = tokens.iter().position(|t| t == "{") {
+        let close = match matching(tokens, open, "{", "}") {
+            Some(close) => close,
+            None => return Vec::new(),
+        };
+        let _type = parse_tagged_body(tokens, open, None, header)
+            .unwrap_or_else(|| format!("{} {{ ... }}", tokens[0]));
+        let base = tokenize(&_type);
+        return split_top_level(&tokens[close + 1..], ",")
+            .iter()
+            .filter_map(|declarator| {
+                let full: Vec<String> = base.iter().chain(declarator.iter()).cloned().collect();
+                parse_declarator(&full)
+            })
+            .collect();
+    }
+
+    let declarators = split_top_level(tokens, ",");
+    let mut fields = Vec::new();
+    let mut base: Vec<String> = Vec::new();
+
+    for (i, declarator) in declarators.iter().enumerate() {
+        // Drop bit-field widths.
+        let declarator: Vec<String> = match declarator.iter().position(|t| t == ":") {
+            Some(colon) => declarator[..colon].to_vec(),
+            None => declarator.clone(),
+        };
+
+        let full: Vec<String> = if i == 0 {
+            declarator.clone()
+        } else {
+            base.iter().chain(declarator.iter()).cloned().collect()
+        };
+
+        if let Some(field) = parse_declarator(&full) {
+            if i == 0 {
+                base = base_type(&field._type);
+            }
+            fields.push(field);
+        }
+    }
+
+    fields
+}
+
+fn parse_typedef(tokens: &[String], header: &mut CHeader) {
+    if let Some(open) = tokens.iter().position(|t| t == "{") {
+        let close = match matching(tokens, open, "{", "}") {
+            Some(close) => close,
+            None => return,
+        };
+        let declarators = split_top_level(&tokens[close + 1..], ",");
+        let alias = declarators
+            .first()
+            .and_then(|d| d.iter().rev().find(|t| is_identifier(t)))
+            .map(String::as_str);
+
+        if let Some(_type) = parse_tagged_body(tokens, open, alias, header) {
+            let base = tokenize(&_type);
+            for declarator in declarators.iter() {
+                let full: Vec<String> = base.iter().chain(declarator.iter()).cloned().collect();
+                push_typedef(parse_declarator(&full), header);
+            }
+        }
+        return;
+    }
+
+    // Later declarators share the first one's base type:
+    // `typedef unsigned long size_t, *size_p;`.
+    let mut base: Vec<String> = Vec::new();
+
+    for (i, declarator) in split_top_level(tokens, ",").into_iter().enumerate() {
+        let full: Vec<String> = if i == 0 {
+            declarator
+        } else {
+            base.iter().cloned().chain(declarator).collect()
+        };
+        let declaration = parse_declarator(&full);
+
+        if i == 0 {
+            match &declaration {
+                Some(declaration) => base = base_type(&declaration._type),
+                None => return,
+            }
+        }
+        push_typedef(declaration, header);
+    }
+}
+
+fn push_typedef(declaration: Option<CDeclaration>, header: &mut CHeader) {
+    if let Some(declaration) = declaration.filter(|d| !d.name.is_empty()) {
+        header.typedefs.push(CTypedef {
+            name: declaration.name,
+            _type: declaration._type,
+        });
+    }
+}
+
+/// The tokens of a declared type before its pointer, array or function
+/// declarator parts, shared by the rest of a declaration's declarators.
+fn base_type(_type: &str) -> Vec<String> {
+    tokenize(_type)
+        .into_iter()
+        .take_while(|t| t != "*" && t != "[" && t != "(")
+        .collect()
+}
+
+fn parse_function(tokens: &[String]) -> Option<CFunction> {
+    let open = tokens.iter().position(|t| t == "(")?;
+
+    if open == 0 || !is_identifier(&tokens[open - 1]) || is_type_keyword(&tokens[open - 1]) {
+        return None;
+    }
+
+    let close = matching(tokens, open, "(", ")")?;
+
+    // Anything but attributes after the parameter list means this isn't a
+    // plain prototype (e.g. a function returning a function pointer).
+    if close + 1 < tokens.len() {
+        return None;
+    }
+
+    let returns = join_tokens(&tokens[..open - 1]);
+
+    if returns.is_empty() {
+        return None;
+    }
+
+    let parameter_tokens = &tokens[open + 1..close];
+    let mut parameters = Vec::new();
+
+    if !(parameter_tokens.is_empty()
+        || parameter_tokens.len() == 1 && parameter_tokens[0] == "void")
+    {
+        for parameter in split_top_level(parameter_tokens, ",") {
+            if parameter.len() == 1 && parameter[0] == "..." {
+                parameters.push(CDeclaration {
+                    name: String::from("..."),
+                    _type: String::new(),
+                });
+            } else if let Some(declaration) = parse_declarator(&parameter) {
+                parameters.push(declaration);
+            }
+        }
+    }
+
+    Some(CFunction {
+        name: tokens[open - 1].clone(),
+        returns,
+        parameters,
+    })
+}
+
+/// Splits `type name` declarators, including function pointers
+/// (`void (*name)(int)`) and arrays (`char name[16]`). Unnamed declarators
+/// get an empty name.
+fn parse_declarator(tokens: &[String]) -> Option<CDeclaration> {
+    if tokens.is_empty() {
+        return None;
+    }
+
+    // Function pointer: `ret ( * name ) ( args )`.
+    if let Some(open) = tokens.windows(2).position(|w| w[0] == "(" && w[1] == "*") {
+        let close = matching(tokens, open, "(", ")")?;
+        let name_index = (open + 2..close).rev().find(|i| is_identifier(&tokens[*i]));
+        let mut _type: Vec<String> = tokens.to_vec();
+        let name = match name_index {
+            Some(index) => _type.remove(index),
+            None => String::new(),
+        };
+
+        return Some(CDeclaration {
+            name,
+            _type: join_tokens(&_type),
+        });
+    }
+
+    let (declarator, array) = match tokens.iter().position(|t| t == "[") {
+        Some(index) => (&tokens[..index], &tokens[index..]),
+        None => (tokens, &tokens[tokens.len()..]),
+    };
+
+    let last = declarator.last()?;
+    let named = declarator.len() > 1
+        && is_identifier(last)
+        && !is_type_keyword(last)
+        && !matches!(
+            declarator[declarator.len() - 2].as_str(),
+            "struct" | "union" | "enum"
+        );
+
+    let (name, type_tokens) = if named {
+        (last.clone(), &declarator[..declarator.len() - 1])
+    } else {
+        (String::new(), declarator)
+    };
+
+    let mut _type = join_tokens(type_tokens);
+
+    if !array.is_empty() {
+        _type = format!("{} {}", _type, join_tokens(array));
+    }
+
+    Some(CDeclaration { name, _type })
+}
+
+fn split_top_level(tokens: &[String], separator: &str) -> Vec<Vec<String>> {
+    let mut parts = Vec::new();
+    let mut current = Vec::new();
+    let mut depth = 0;
+
+    for token in tokens.iter() {
+        match token.as_str() {
+            "(" | "{" | "[" => depth += 1,
+            ")" | "}" | "]" => depth -= 1,
+            _ => {}
+        }
+
+        if depth == 0 && token == separator {
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(token.clone());
+        }
+    }
+
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    parts
+}
+
+/// Joins tokens back into a type string in the corpus' style
+/// (`const char *`, `void (*)(int)`, `char [16]`).
+fn join_tokens(tokens: &[String]) -> String {
+    let mut out = String::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 {
+            let previous = tokens[i - 1].as_str();
+            let tight = matches!(previous, "(" | "[")
+                || matches!(token.as_str(), ")" | "]" | "," | "[")
+                || (previous == ")" && token == "(")
+                || (previous == "*" && token == "*");
+
+            if !tight {
+                out.push(' ');
+            }
+        }
+
+        out.push_str(token);
+    }
+
+    out
+}
+
+fn is_identifier(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && token.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn is_type_keyword(token: &str) -> bool {
+    TYPE_KEYWORDS.contains(&token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unbalanced_groups_are_skipped() {
+        for source in [
+            "int f(",
+            "int f(int a",
+            "struct s {",
+            "struct s { int a;",
+            "typedef struct {",
+            "typedef void (*",
+            "int f(void) __NTH (",
+            "int f(void) __attribute__ (",
+            "void (*signal(int, void (*)(int)))(",
+        ] {
+            let header = parse(source);
+            assert!(header.functions.is_empty(), "{}", source);
+            assert!(header.structs.is_empty(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn unbalanced_tail_keeps_earlier_declarations() {
+        let header = parse("int puts(const char *s);\nstruct s { int a;");
+
+        assert_eq!(header.functions.len(), 1);
+        assert_eq!(header.functions[0].name, "puts");
+    }
+
+    #[test]
+    fn matching_finds_the_closing_token() {
+        let tokens = tokenize("( a ( b ) c )");
+
+        assert_eq!(matching(&tokens, 0, "(", ")"), Some(6));
+        assert_eq!(matching(&tokens, 2, "(", ")"), Some(4));
+        assert_eq!(matching(&tokens[..6], 0, "(", ")"), None);
+    }
+
+    /// Name, return type and parameters of a parsed function.
+    type Prototype<'a> = (&'a str, &'a str, Vec<(&'a str, &'a str)>);
+
+    fn declarations(declarations: &[CDeclaration]) -> Vec<(&str, &str)> {
+        declarations
+            .iter()
+            .map(|d| (d.name.as_str(), d._type.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_splits_punctuation_and_keeps_literals() {
+        assert_eq!(
+            tokenize("printf(\"a, b\", x->y...)"),
+            vec!["printf", "(", "\"a, b\"", ",", "x", "-", ">", "y", "...", ")"]
+        );
+    }
+
+    #[test]
+    fn source_tokens_drop_comments_and_includes() {
+        assert_eq!(
+            source_tokens("#include <stdio.h>\n/* puts( */ puts(\"x\"); // y(\n"),
+            vec!["puts", "(", "\"x\"", ")", ";"]
+        );
+    }
+
+    #[test]
+    fn normalize_type_drops_qualifiers_and_spacing() {
+        assert_eq!(normalize_type("const  char *__restrict"), "const char *");
+        assert_eq!(normalize_type("register int"), "int");
+    }
+
+    fn macro_names(header: &CHeader) -> Vec<&str> {
+        header.macros.iter().map(|m| m.name.as_str()).collect()
+    }
+
+    #[test]
+    fn defines_and_includes_are_recorded() {
+        let header = parse(
+            "#ifndef _STDIO_H\n#define _STDIO_H 1\n#include <stddef.h>\n#define EOF (-1)\n\
+             #define getc(fp) _IO_getc (fp)\n#define WRAP (x)\n#ifdef __cplusplus\n\
+             #define CPLUSPLUS_ONLY 1\n#endif\n#endif\n",
+        );
+        let parameters: Vec<Option<&Vec<String>>> = header
+            .macros
+            .iter()
+            .map(|m| m.parameters.as_ref())
+            .collect();
+
+        assert_eq!(header.includes, vec!["stddef.h"]);
+        assert_eq!(macro_names(&header), vec!["EOF", "getc", "WRAP"]);
+        assert_eq!(
+            parameters,
+            vec![None, Some(&vec![String::from("fp")]), None]
+        );
+    }
+
+    #[test]
+    fn only_guarding_defines_are_dropped() {
+        let header = parse(
+            "#ifndef _IO_H_\n#define _IO_H_\n#define _IO_H_FLAG 0x1\n#define SIG_H_MASK 2\n\
+             #ifndef BUFSIZ\n#define BUFSIZ 8192\n#endif\n\
+             #ifndef _TIME_H\nstruct tm;\n#define _TIME_H\n#endif\n#endif\n",
+        );
+
+        assert_eq!(
+            macro_names(&header),
+            vec!["_IO_H_FLAG", "SIG_H_MASK", "BUFSIZ", "_TIME_H"]
+        );
+    }
+
+    #[test]
+    fn prototypes_lose_attributes() {
+        let header = parse(
+            "extern int printf (const char *__restrict __format, ...)\n\
+             __attribute__ ((__format__ (__printf__, 1, 2))) __THROW;\n\
+             extern int atexit (void (*__func) (void)) __THROW __nonnull ((1));\n\
+             extern char *gets (char buf[]) __wur;\n\
+             size_t strlen(const char *) __THROW;\n\
+             static inline int zero(void) { return 0; }\n",
+        );
+        let functions: Vec<Prototype> = header
+            .functions
+            .iter()
+            .map(|f| {
+                (
+                    f.name.as_str(),
+                    f.returns.as_str(),
+                    declarations(&f.parameters),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            functions,
+            vec![
+                (
+                    "printf",
+                    "int",
+                    vec![("__format", "const char * restrict"), ("...", "")]
+                ),
+                ("atexit", "int", vec![("__func", "void (*)(void)")]),
+                ("gets", "char *", vec![("buf", "char []")]),
+                ("strlen", "size_t", vec![("", "const char *")]),
+                ("zero", "int", vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn structs_enums_and_typedefs_are_recorded() {
+        let header = parse(
+            "struct tm { int tm_sec; unsigned flag : 1, other : 2; struct { int a; } inner, *next; };\n\
+             enum clock { CLOCK_A = 0, CLOCK_B, };\n\
+             typedef struct { int x; } point_t, *point_p, points_t[4];\n\
+             typedef struct { int y; } *anonymous_p;\n\
+             typedef void (*handler_t)(int);\n\
+             typedef unsigned long size_t, *size_p;\n",
+        );
+
+        let tm = header._struct("tm").unwrap();
+        assert_eq!(
+            declarations(&tm.fields),
+            vec![
+                ("tm_sec", "int"),
+                ("flag", "unsigned"),
+                ("other", "unsigned"),
+                ("inner", "struct { ... }"),
+                ("next", "struct { ... } *")
+            ]
+        );
+        assert_eq!(
+            declarations(&header._struct("point_t").unwrap().fields),
+            vec![("x", "int")]
+        );
+        assert_eq!(
+            header._enum("clock").unwrap().variants,
+            vec!["CLOCK_A", "CLOCK_B"]
+        );
+
+        let typedefs: Vec<(&str, &str)> = header
+            .typedefs
+            .iter()
+            .map(|t| (t.name.as_str(), t._type.as_str()))
+            .collect();
+        assert_eq!(
+            typedefs,
+            vec![
+                ("point_t", "struct { ... }"),
+                ("point_p", "struct { ... } *"),
+                ("points_t", "struct { ... } [4]"),
+                ("anonymous_p", "struct { ... } *"),
+                ("handler_t", "void (*)(int)"),
+                ("size_t", "unsigned long"),
+                ("size_p", "unsigned long *")
+            ]
+        );
+    }
+}
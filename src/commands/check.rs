@@ -0,0 +1,669 @@
+use crate::{
+    attributes, c_parser,
+    definitions::{_macro::MacroKind, attributes::Attributes, deprecated::Deprecated},
+    discover_document,
+    exporters::{referenced_types, typedef_target, EntityRef},
+    Document,
+};
+use std::process;
+
+/// Placeholder left by `scaffold` wherever a human still has to write text.
+pub(crate) const TODO_MARKER: &str = "TODO";
+
+/// A problem a lint found, located by corpus file (`stdio/fn-printf`).
+pub(crate) struct Finding {
+    pub lint: &'static str,
+    pub location: String,
+    pub message: String,
+}
+
+type Lint = fn(&Document) -> Vec<Finding>;
+
+const LINTS: &[Lint] = &[todo_markers, portability, deprecated_calls, signal_safety];
+
+/// `check <corpus path>`
+///
+/// Runs every lint over the corpus and prints what they find. Exits non-zero
+/// when anything is reported.
+pub(crate) fn run(args: &[String]) {
+    if args.is_empty() {
+        eprintln!("Usage: capibara-processor check <corpus path>");
+        process::exit(2);
+    }
+
+    let filepath = &args[0];
+    println!("Filepath:\t\t{}", filepath);
+
+    let document = discover_document(filepath, "");
+    let mut findings: Vec<Finding> = LINTS.iter().flat_map(|lint| lint(&document)).collect();
+    findings.sort_by(|a, b| a.location.cmp(&b.location).then(a.lint.cmp(b.lint)));
+
+    println!();
+    for finding in findings.iter() {
+        println!(
+            "{}: {} [{}]",
+            finding.location, finding.message, finding.lint
+        );
+    }
+
+    println!("\n{} finding(s)", findings.len());
+
+    if !findings.is_empty() {
+        process::exit(1);
+    }
+}
+
+/// Fields still holding the `scaffold` placeholder.
+fn todo_markers(document: &Document) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut check = |location: String, field: String, value: &str| {
+        if value.contains(TODO_MARKER) {
+            findings.push(Finding {
+                lint: "todo",
+                location,
+                message: format!("{} is still a TODO", field),
+            });
+        }
+    };
+
+    for header in document.headers.iter() {
+        check(
+            format!("{}/meta", header._ref),
+            String::from("summary"),
+            &header.summary,
+        );
+    }
+
+    for function in document.functions.iter() {
+        let location = format!("{}/fn-{}", function.header._ref, function.name);
+        check(location.clone(), String::from("summary"), &function.summary);
+        check(
+            location.clone(),
+            String::from("description"),
+            &function.description,
+        );
+        check(
+            location.clone(),
+            String::from("return type"),
+            &function.returns._type,
+        );
+        check(
+            location.clone(),
+            String::from("return description"),
+            &function.returns.description,
+        );
+        for parameter in function.parameters.iter() {
+            check(
+                location.clone(),
+                format!("parameter `{}` name", parameter.name),
+                &parameter.name,
+            );
+            check(
+                location.clone(),
+                format!("parameter `{}` description", parameter.name),
+                &parameter.description,
+            );
+        }
+    }
+
+    for _macro in document.macros.iter() {
+        let location = format!("{}/mo-{}", _macro.header._ref, _macro.name);
+        check(location.clone(), String::from("summary"), &_macro.summary);
+        check(
+            location.clone(),
+            String::from("description"),
+            &_macro.description,
+        );
+        if let MacroKind::Function(function) = &_macro.kind {
+            check(
+                location.clone(),
+                String::from("return type"),
+                &function.returns._type,
+            );
+            check(
+                location.clone(),
+                String::from("return description"),
+                &function.returns.description,
+            );
+            for parameter in function.parameters.iter() {
+                check(
+                    location.clone(),
+                    format!("parameter `{}` description", parameter.name),
+                    &parameter.description,
+                );
+            }
+        }
+    }
+
+    for _enum in document.enums.iter() {
+        let location = format!("{}/em-{}", _enum.header._ref, _enum.name);
+        check(location.clone(), String::from("summary"), &_enum.summary);
+        check(
+            location.clone(),
+            String::from("description"),
+            &_enum.description,
+        );
+        for variant in _enum.variants.iter() {
+            check(
+                location.clone(),
+                format!("variant `{}` description", variant.name),
+                &variant.description,
+            );
+        }
+    }
+
+    for _struct in document.structs.iter() {
+        let location = format!("{}/st-{}", _struct.header._ref, _struct.name);
+        check(location.clone(), String::from("summary"), &_struct.summary);
+        check(
+            location.clone(),
+            String::from("description"),
+            &_struct.description,
+        );
+        for field in _struct.fields.iter() {
+            check(
+                location.clone(),
+                format!("field `{}` description", field.name),
+                &field.description,
+            );
+        }
+    }
+
+    for typedef in document.typedefs.iter() {
+        let location = format!("{}/tf-{}", typedef.header._ref, typedef.name);
+        check(location.clone(), String::from("summary"), &typedef.summary);
+        check(
+            location.clone(),
+            String::from("description"),
+            &typedef.description,
+        );
+    }
+
+    findings
+}
+
+/// Entities claiming an OS that a type they depend on doesn't support:
+/// parameter, return and field types, typedef types and `associated_ref`s.
+fn portability(document: &Document) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut check = |location: String, os_affinity: &[String], uses: String, _type: &str| {
+        for dependency in referenced_types(document, _type) {
+            report_unsupported(&mut findings, &location, os_affinity, &uses, dependency);
+        }
+    };
+
+    for function in document.functions.iter() {
+        let location = format!("{}/fn-{}", function.header._ref, function.name);
+        check(
+            location.clone(),
+            &function.os_affinity,
+            String::from("return type"),
+            &function.returns._type,
+        );
+        for parameter in function.parameters.iter() {
+            check(
+                location.clone(),
+                &function.os_affinity,
+                format!("parameter `{}`", parameter.name),
+                &parameter._type,
+            );
+        }
+    }
+
+    for _macro in document.macros.iter() {
+        if let MacroKind::Function(function) = &_macro.kind {
+            check(
+                format!("{}/mo-{}", _macro.header._ref, _macro.name),
+                &_macro.os_affinity,
+                String::from("return type"),
+                &function.returns._type,
+            );
+        }
+    }
+
+    for _struct in document.structs.iter() {
+        let location = format!("{}/st-{}", _struct.header._ref, _struct.name);
+        for field in _struct.fields.iter() {
+            check(
+                location.clone(),
+                &_struct.os_affinity,
+                format!("field `{}`", field.name),
+                &field._type,
+            );
+        }
+    }
+
+    for typedef in document.typedefs.iter() {
+        check(
+            format!("{}/tf-{}", typedef.header._ref, typedef.name),
+            &typedef.os_affinity,
+            String::from("type"),
+            &typedef._type,
+        );
+    }
+
+    // `associated_ref` can name a type the `type` string doesn't.
+    for typedef in document.typedefs.iter() {
+        if let Some(target) = typedef_target(typedef) {
+            let location = format!("{}/tf-{}", typedef.header._ref, typedef.name);
+            let already_checked = referenced_types(document, &typedef._type)
+                .iter()
+                .any(|r| r.kind == target.kind && r.name == target.name);
+
+            if !already_checked {
+                report_unsupported(
+                    &mut findings,
+                    &location,
+                    &typedef.os_affinity,
+                    "associated_ref",
+                    target,
+                );
+            }
+        }
+    }
+
+    findings
+}
+
+fn report_unsupported(
+    findings: &mut Vec<Finding>,
+    location: &str,
+    os_affinity: &[String],
+    uses: &str,
+    dependency: EntityRef,
+) {
+    let unsupported: Vec<&str> = os_affinity
+        .iter()
+        .filter(|os| !dependency.os_affinity.contains(os))
+        .map(|os| os.as_str())
+        .collect();
+
+    if !unsupported.is_empty() {
+        findings.push(Finding {
+            lint: "portability",
+            location: location.to_string(),
+            message: format!(
+                "claims {}, but its {} uses {} {}/{} which doesn't support it",
+                unsupported.join(", "),
+                uses,
+                dependency.kind.to_lowercase(),
+                dependency.header._ref,
+                dependency.name
+            ),
+        });
+    }
+}
+
+/// Examples of function and function-like macro pages, with the page's
+/// location and deprecation.
+fn examples(document: &Document) -> Vec<(String, Option<&Deprecated>, &str, &str)> {
+    let mut examples = Vec::new();
+
+    for function in document.functions.iter() {
+        for example in function.examples.iter() {
+            examples.push((
+                format!("{}/fn-{}", function.header._ref, function.name),
+                function.deprecated.as_ref(),
+                example.title.as_str(),
+                example.code.as_str(),
+            ));
+        }
+    }
+
+    for _macro in document.macros.iter() {
+        if let MacroKind::Function(function) = &_macro.kind {
+            for example in function.examples.iter() {
+                examples.push((
+                    format!("{}/mo-{}", _macro.header._ref, _macro.name),
+                    _macro.deprecated.as_ref(),
+                    example.title.as_str(),
+                    example.code.as_str(),
+                ));
+            }
+        }
+    }
+
+    examples
+}
+
+/// Names called in `tokens`, in order, skipping member accesses
+/// (`ops.gets(...)`) which call through pointers.
+fn calls(tokens: &[String]) -> Vec<&str> {
+    tokens
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            let previous = if *i > 0 { tokens[i - 1].as_str() } else { "" };
+            previous != "." && previous != ">"
+        })
+        .filter(|(i, _)| tokens.get(i + 1).map(|t| t.as_str()) == Some("("))
+        .map(|(_, token)| token.as_str())
+        .collect()
+}
+
+/// Examples of entities that aren't deprecated themselves calling a
+/// deprecated function or function-like macro.
+fn deprecated_calls(document: &Document) -> Vec<Finding> {
+    let mut callables: Vec<(&str, String, &Deprecated)> = Vec::new();
+
+    for function in document.functions.iter() {
+        if let Some(deprecated) = &function.deprecated {
+            callables.push((
+                &function.name,
+                format!("function {}/{}", function.header._ref, function.name),
+                deprecated,
+            ));
+        }
+    }
+
+    for _macro in document.macros.iter() {
+        if let (Some(deprecated), MacroKind::Function(_)) = (&_macro.deprecated, &_macro.kind) {
+            callables.push((
+                &_macro.name,
+                format!("macro {}/{}", _macro.header._ref, _macro.name),
+                deprecated,
+            ));
+        }
+    }
+
+    let mut findings = Vec::new();
+
+    for (location, deprecated, title, code) in examples(document) {
+        if deprecated.is_some() {
+            continue;
+        }
+
+        let tokens = c_parser::source_tokens(code);
+        let mut reported: Vec<&str> = Vec::new();
+
+        for call in calls(&tokens) {
+            for (name, callee, deprecated) in callables.iter() {
+                if call == *name && !reported.contains(name) {
+                    reported.push(name);
+                    findings.push(Finding {
+                        lint: "deprecated",
+                        location: location.clone(),
+                        message: format!(
+                            "example \"{}\" calls deprecated {}{}",
+                            title,
+                            callee,
+                            match &deprecated.replacement {
+                                Some(replacement) => format!(", use {} instead", replacement),
+                                None => String::new(),
+                            }
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    findings
+}
+
+/// Examples whose signal handlers call functions or function-like macros
+/// documented as AS-Unsafe. Handlers are the functions an example installs
+/// with `signal()` or through a `sigaction`'s `sa_handler`/`sa_sigaction`
+/// and also defines.
+fn signal_safety(document: &Document) -> Vec<Finding> {
+    let mut callables: Vec<(&str, String)> = Vec::new();
+
+    for function in document.functions.iter() {
+        if is_as_unsafe(function.attributes.as_ref()) {
+            callables.push((
+                &function.name,
+                format!("function {}/{}", function.header._ref, function.name),
+            ));
+        }
+    }
+
+    for _macro in document.macros.iter() {
+        if let MacroKind::Function(function) = &_macro.kind {
+            if is_as_unsafe(function.attributes.as_deref()) {
+                callables.push((
+                    &_macro.name,
+                    format!("macro {}/{}", _macro.header._ref, _macro.name),
+                ));
+            }
+        }
+    }
+
+    let mut findings = Vec::new();
+
+    for (location, _, title, code) in examples(document) {
+        let tokens = c_parser::source_tokens(code);
+
+        for handler in signal_handlers(&tokens) {
+            let body = match handler_body(&tokens, handler) {
+                Some(body) => body,
+                None => continue,
+            };
+            let mut reported: Vec<&str> = Vec::new();
+
+            for call in calls(body) {
+                for (name, callee) in callables.iter() {
+                    if call == *name && !reported.contains(name) {
+                        reported.push(name);
+                        findings.push(Finding {
+                            lint: "signal-safety",
+                            location: location.clone(),
+                            message: format!(
+                                "example \"{}\" calls AS-Unsafe {} in signal handler {}",
+                                title, callee, handler
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    findings
+}
+
+fn is_as_unsafe(attributes: Option<&Attributes>) -> bool {
+    attributes
+        .and_then(|a| a.async_signal_safety.as_ref())
+        .is_some_and(|s| s.level == attributes::AS_UNSAFE)
+}
+
+/// Names installed as signal handlers: the last argument of `signal(...)`
+/// and whatever is assigned to `sa_handler` or `sa_sigaction`.
+fn signal_handlers(tokens: &[String]) -> Vec<&str> {
+    let mut handlers: Vec<&str> = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        let handler = match token.as_str() {
+            "signal" if tokens.get(i + 1).map(|t| t.as_str()) == Some("(") => {
+                match c_parser::matching(tokens, i + 1, "(", ")") {
+                    Some(close) if close > i + 2 && tokens[close - 2] == "," => {
+                        tokens[close - 1].as_str()
+                    }
+                    _ => continue,
+                }
+            }
+            "sa_handler" | "sa_sigaction" if tokens.get(i + 1).map(|t| t.as_str()) == Some("=") => {
+                match tokens.get(i + 2) {
+                    Some(handler) => handler.as_str(),
+                    None => continue,
+                }
+            }
+            _ => continue,
+        };
+
+        if is_identifier(handler) && !handlers.contains(&handler) {
+            handlers.push(handler);
+        }
+    }
+
+    handlers
+}
+
+/// Tokens of the body `handler` is defined with in the example, braces
+/// included.
+fn handler_body<'a>(tokens: &'a [String], handler: &str) -> Option<&'a [String]> {
+    tokens
+        .iter()
+        .enumerate()
+        .filter(|(i, token)| {
+            *token == handler && tokens.get(i + 1).map(|t| t.as_str()) == Some("(")
+        })
+        .find_map(|(i, _)| {
+            let close = c_parser::matching(tokens, i + 1, "(", ")")?;
+            if tokens.get(close + 1).map(|t| t.as_str()) != Some("{") {
+                return None;
+            }
+            let end = c_parser::matching(tokens, close + 1, "{", "}")?;
+            Some(&tokens[close + 1..=end])
+        })
+}
+
+fn is_identifier(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{_struct, document, function, function_macro, header, typedef};
+    use serde_json::json;
+
+    fn messages(findings: &[Finding]) -> Vec<String> {
+        findings
+            .iter()
+            .map(|f| format!("{} {}: {}", f.lint, f.location, f.message))
+            .collect()
+    }
+
+    #[test]
+    fn portability_flags_dependencies_on_narrower_types() {
+        let mut fstat = function("stat", "fstat", &["linux", "windows"]);
+        fstat["parameters"] = json!([
+            { "name": "fd", "type": "int", "description": "A descriptor." },
+            { "name": "buf", "type": "struct stat *", "description": "The result." },
+        ]);
+        let mut stat_p = typedef("stat", "stat_p", "void *", &["linux", "macos"]);
+        stat_p["associated_ref"] = json!({ "struct": _struct("stat", "stat", &["linux"]) });
+
+        let document = document(
+            vec![header("stat", &["linux", "macos", "windows"])],
+            vec![fstat],
+            vec![],
+            vec![_struct("stat", "stat", &["linux"])],
+            vec![stat_p],
+        );
+
+        assert_eq!(
+            messages(&portability(&document)),
+            vec![
+                "portability stat/fn-fstat: claims windows, but its parameter `buf` uses struct stat/stat which doesn't support it",
+                "portability stat/tf-stat_p: claims macos, but its associated_ref uses struct stat/stat which doesn't support it",
+            ]
+        );
+    }
+
+    #[test]
+    fn portability_accepts_types_covering_the_claim() {
+        let mut stat_t = typedef("stat", "stat_t", "struct stat", &["linux"]);
+        stat_t["associated_ref"] =
+            json!({ "struct": _struct("stat", "stat", &["linux", "macos"]) });
+
+        let document = document(
+            vec![header("stat", &["linux", "macos"])],
+            vec![],
+            vec![],
+            vec![_struct("stat", "stat", &["linux", "macos"])],
+            vec![stat_t],
+        );
+
+        assert!(portability(&document).is_empty());
+    }
+
+    #[test]
+    fn deprecated_calls_flag_examples_calling_deprecated_callables() {
+        let mut gets = function("stdio", "gets", &["linux"]);
+        gets["deprecated"] = json!({ "reason": "Unbounded.", "replacement": "stdio/fgets" });
+        let mut getc = function_macro("stdio", "getc", &["fp"], &["linux"]);
+        getc["deprecated"] = json!({ "reason": "Use fgetc." });
+
+        let mut fgets = function("stdio", "fgets", &["linux"]);
+        fgets["examples"] = json!([
+            { "title": "Old", "code": "gets(buf); gets(buf); getc(stdin);" },
+            { "title": "Member", "code": "ops.gets(buf); ops->gets(buf); /* gets(buf) */" },
+        ]);
+
+        let document = document(
+            vec![header("stdio", &["linux"])],
+            vec![gets, fgets],
+            vec![getc],
+            vec![],
+            vec![],
+        );
+
+        assert_eq!(
+            messages(&deprecated_calls(&document)),
+            vec![
+                "deprecated stdio/fn-fgets: example \"Old\" calls deprecated function stdio/gets, use stdio/fgets instead",
+                "deprecated stdio/fn-fgets: example \"Old\" calls deprecated macro stdio/getc",
+            ]
+        );
+    }
+
+    #[test]
+    fn deprecated_callables_may_call_each_other() {
+        let mut gets = function("stdio", "gets", &["linux"]);
+        gets["deprecated"] = json!({ "reason": "Unbounded." });
+        gets["examples"] = json!([{ "title": "Loop", "code": "while (gets(buf)) {}" }]);
+
+        let document = document(
+            vec![header("stdio", &["linux"])],
+            vec![gets],
+            vec![],
+            vec![],
+            vec![],
+        );
+
+        assert!(deprecated_calls(&document).is_empty());
+    }
+
+    #[test]
+    fn signal_safety_flags_as_unsafe_calls_in_installed_handlers() {
+        let as_unsafe = json!({ "async_signal_safety": { "level": "AS-Unsafe" } });
+        let mut printf = function("stdio", "printf", &["linux"]);
+        printf["attributes"] = as_unsafe.clone();
+        let mut putc = function_macro("stdio", "putc", &["c", "fp"], &["linux"]);
+        putc["kind"]["function"]["attributes"] = as_unsafe;
+
+        let mut signal = function("signal", "signal", &["linux"]);
+        signal["examples"] = json!([
+            {
+                "title": "Handler",
+                "code": "void on_int(int sig) { printf(\"%d\", sig); putc('x', stderr); }\nint main(void) { signal(SIGINT, on_int); printf(\"ok\"); }",
+            },
+            {
+                "title": "Not installed",
+                "code": "void helper(int sig) { printf(\"%d\", sig); }",
+            },
+        ]);
+
+        let document = document(
+            vec![header("stdio", &["linux"]), header("signal", &["linux"])],
+            vec![printf, signal],
+            vec![putc],
+            vec![],
+            vec![],
+        );
+
+        assert_eq!(
+            messages(&signal_safety(&document)),
+            vec![
+                "signal-safety signal/fn-signal: example \"Handler\" calls AS-Unsafe function stdio/printf in signal handler on_int",
+                "signal-safety signal/fn-signal: example \"Handler\" calls AS-Unsafe macro stdio/putc in signal handler on_int",
+            ]
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{c_parser, discover_document, exporters, Document};
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, process};
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CoverageReport {
+    pub documented: usize,
+    pub total: usize,
+    pub percentage: f64,
+    pub headers: Vec<HeaderCoverage>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct HeaderCoverage {
+    pub header: String,
+    pub path: String,
+    pub documented: usize,
+    pub total: usize,
+    pub percentage: f64,
+    pub functions: KindCoverage,
+    pub macros: KindCoverage,
+    pub structs: KindCoverage,
+    pub enums: KindCoverage,
+    pub typedefs: KindCoverage,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct KindCoverage {
+    pub documented: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+/// `coverage <corpus path> <include dir>... [--json <file>]`
+///
+/// Extracts the symbols each documented header declares in the real header
+/// file, following its includes (e.g. into `bits/`) but not into headers the
+/// corpus documents separately, and reports which of them the corpus
+/// documents.
+pub(crate) fn run(args: &[String]) {
+    let mut positional = Vec::new();
+    let mut json_path = None;
+    let mut args_iter = args.iter();
+
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--json" => json_path = args_iter.next().cloned(),
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    if positional.len() < 2 {
+        eprintln!(
+            "Usage: capibara-processor coverage <corpus path> <include dir>... [--json <file>]"
+        );
+        process::exit(2);
+    }
+
+    let filepath = &positional[0];
+    println!("Filepath:\t\t{}", filepath);
+    let include_dirs: Vec<PathBuf> = positional[1..].iter().map(PathBuf::from).collect();
+    println!("Include Directories:\t{}", positional[1..].join(", "));
+
+    let document = discover_document(filepath, "");
+    let report = build_report(&document, &include_dirs);
+
+    print_report(&report);
+
+    if let Some(json_path) = json_path {
+        let written = serde_json::to_string_pretty(&report)
+            .map_err(|error| error.to_string())
+            .and_then(|json| std::fs::write(&json_path, json).map_err(|error| error.to_string()));
+
+        if let Err(error) = written {
+            eprintln!("Coverage Error: could not write {}: {}", json_path, error);
+            process::exit(1);
+        }
+    }
+}
+
+pub(crate) fn build_report(document: &Document, include_dirs: &[PathBuf]) -> CoverageReport {
+    let entities = exporters::entities(document);
+    let mut headers = Vec::new();
+    // Symbols of a documented header are counted there, not in every header
+    // that includes it.
+    let documented_paths: Vec<PathBuf> = document
+        .headers
+        .iter()
+        .filter_map(|header| c_parser::find_header(&header.name, include_dirs))
+        .collect();
+
+    for header in document.headers.iter() {
+        let Some(path) = c_parser::find_header(&header.name, include_dirs) else {
+            eprintln!("{}: not found in the include directories", header.name);
+            continue;
+        };
+
+        let c_header =
+            match c_parser::parse_file_with_includes_except(&path, include_dirs, &documented_paths)
+            {
+                Ok(c_header) => c_header,
+                Err(error) => {
+                    eprintln!("{}: could not read {:?}: {}", header.name, path, error);
+                    continue;
+                }
+            };
+
+        let documented_names: Vec<&str> = entities
+            .iter()
+            .filter(|e| e.header._ref == header._ref)
+            .map(|e| e.name)
+            .collect();
+
+        let mut coverage = HeaderCoverage {
+            header: header.name.to_string(),
+            path: path.display().to_string(),
+            documented: 0,
+            total: 0,
+            percentage: 0.0,
+            functions: classify(
+                c_header.functions.iter().map(|f| &f.name),
+                &documented_names,
+            ),
+            // Macros that redirect a declared function (`fopen` to `fopen64`)
+            // are counted once, as the function.
+            macros: classify(
+                c_header
+                    .macros
+                    .iter()
+                    .map(|m| &m.name)
+                    .filter(|name| c_header.function(name).is_none()),
+                &documented_names,
+            ),
+            structs: classify(c_header.structs.iter().map(|s| &s.name), &documented_names),
+            enums: classify(c_header.enums.iter().map(|e| &e.name), &documented_names),
+            typedefs: classify(c_header.typedefs.iter().map(|t| &t.name), &documented_names),
+        };
+
+        for kind in [
+            &coverage.functions,
+            &coverage.macros,
+            &coverage.structs,
+            &coverage.enums,
+            &coverage.typedefs,
+        ] {
+            coverage.documented += kind.documented.len();
+            coverage.total += kind.documented.len() + kind.missing.len();
+        }
+        coverage.percentage = percentage(coverage.documented, coverage.total);
+
+        headers.push(coverage);
+    }
+
+    let documented = headers.iter().map(|h| h.documented).sum();
+    let total = headers.iter().map(|h| h.total).sum();
+
+    CoverageReport {
+        documented,
+        total,
+        percentage: percentage(documented, total),
+        headers,
+    }
+}
+
+/// Splits declared names into documented and missing, skipping the
+/// implementation's reserved `_` namespace. A name counts as documented
+/// whatever kind the corpus files it under (e.g. `getc` as a macro).
+fn classify<'a>(
+    names: impl Iterator<Item = &'a String>,
+    documented_names: &[&str],
+) -> KindCoverage {
+    let mut names: Vec<&String> = names.filter(|n| !n.starts_with('_')).collect();
+    names.sort();
+    names.dedup();
+
+    let mut coverage = KindCoverage::default();
+
+    for name in names {
+        if documented_names.contains(&name.as_str()) {
+            coverage.documented.push(name.clone());
+        } else {
+            coverage.missing.push(name.clone());
+        }
+    }
+
+    coverage
+}
+
+fn percentage(documented: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        documented as f64 * 100.0 / total as f64
+    }
+}
+
+fn print_report(report: &CoverageReport) {
+    for header in report.headers.iter() {
+        println!(
+            "\n{}: {}/{} documented ({:.1}%)",
+            header.header, header.documented, header.total, header.percentage
+        );
+
+        for (title, kind) in [
+            ("functions", &header.functions),
+            ("macros", &header.macros),
+            ("structs", &header.structs),
+            ("enums", &header.enums),
+            ("typedefs", &header.typedefs),
+        ] {
+            let total = kind.documented.len() + kind.missing.len();
+
+            if total == 0 {
+                continue;
+            }
+
+            println!("  {}: {}/{}", title, kind.documented.len(), total);
+
+            if !kind.missing.is_empty() {
+                println!("    missing: {}", kind.missing.join(", "));
+            }
+        }
+    }
+
+    println!(
+        "\nOverall: {}/{} documented ({:.1}%)",
+        report.documented, report.total, report.percentage
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{document, header, object_macro, temp_dir, typedef};
+    use std::fs;
+
+    #[test]
+    fn coverage_follows_includes_into_undocumented_headers() {
+        let include_dir = temp_dir("coverage");
+        fs::create_dir_all(include_dir.join("bits")).unwrap();
+        fs::write(
+            include_dir.join("errno.h"),
+            "#ifndef _ERRNO_H\n#define _ERRNO_H 1\n#include <bits/errno.h>\n\
+             #include <stddef.h>\nextern int *__errno_location (void);\n#endif\n",
+        )
+        .unwrap();
+        fs::write(
+            include_dir.join("bits/errno.h"),
+            "#define EINVAL 22\n#define ENOMEM 12\n",
+        )
+        .unwrap();
+        fs::write(
+            include_dir.join("stddef.h"),
+            "typedef unsigned long size_t;\n",
+        )
+        .unwrap();
+
+        let document = document(
+            vec![header("errno", &["linux"]), header("stddef", &["linux"])],
+            vec![],
+            vec![object_macro("errno", "EINVAL", &["linux"])],
+            vec![],
+            vec![typedef("stddef", "size_t", "unsigned long", &["linux"])],
+        );
+        let report = build_report(&document, std::slice::from_ref(&include_dir));
+        fs::remove_dir_all(&include_dir).unwrap();
+
+        let errno = &report.headers[0];
+        assert_eq!(errno.macros.documented, vec!["EINVAL"]);
+        assert_eq!(errno.macros.missing, vec!["ENOMEM"]);
+        assert_eq!((errno.documented, errno.total), (1, 2));
+
+        let stddef = &report.headers[1];
+        assert_eq!(stddef.typedefs.documented, vec!["size_t"]);
+        assert_eq!((report.documented, report.total), (2, 3));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::discover_document;
+use goblin::elf::{
+    sym::{STB_GLOBAL, STB_WEAK, STT_FUNC, STT_GNU_IFUNC, STV_DEFAULT, STV_PROTECTED},
+    Elf,
+};
+use std::{fs, process};
+
+/// `exports <corpus path> <shared object>...`
+///
+/// Reads the dynamic symbol tables of the given ELF shared objects (e.g.
+/// `libc.so.6` and `libm.so.6`) and reports exported functions with no
+/// `Function` entry, and documented functions none of them export.
+pub(crate) fn run(args: &[String]) {
+    if args.len() < 2 {
+        eprintln!("Usage: capibara-processor exports <corpus path> <shared object>...");
+        process::exit(2);
+    }
+
+    let filepath = &args[0];
+    println!("Filepath:\t\t{}", filepath);
+    println!("Shared Objects:\t\t{}", args[1..].join(", "));
+
+    let mut exported: Vec<String> = Vec::new();
+
+    for object_path in args[1..].iter() {
+        match exported_functions(object_path) {
+            Ok(names) => {
+                println!(
+                    "Found {} exported functions in {}",
+                    names.len(),
+                    object_path
+                );
+                exported.extend(names);
+            }
+            Err(error) => {
+                eprintln!("Exports Error ({}): {}", object_path, error);
+                process::exit(1);
+            }
+        }
+    }
+
+    exported.sort();
+    exported.dedup();
+
+    let document = discover_document(filepath, "");
+
+    // Names in the implementation's reserved `_` namespace are exported for
+    // the compiler and other libraries, not for documentation.
+    let undocumented: Vec<&String> = exported
+        .iter()
+        .filter(|name| !name.starts_with('_'))
+        .filter(|name| !document.functions.iter().any(|f| f.name.as_str() == *name))
+        .collect();
+
+    let unexported: Vec<String> = document
+        .functions
+        .iter()
+        .filter(|f| exported.binary_search(&f.name).is_err())
+        .map(|f| format!("{}/{}", f.header._ref, f.name))
+        .collect();
+
+    println!(
+        "\nExported functions without documentation ({}):",
+        undocumented.len()
+    );
+    for name in undocumented.iter() {
+        println!("  {}", name);
+    }
+
+    println!(
+        "\nDocumented functions that aren't exported ({}):",
+        unexported.len()
+    );
+    for reference in unexported.iter() {
+        println!("  {}", reference);
+    }
+
+    let total = exported
+        .iter()
+        .filter(|name| !name.starts_with('_'))
+        .count();
+    println!(
+        "\n{}/{} exported functions documented",
+        total - undocumented.len(),
+        total
+    );
+}
+
+/// Sorted names of the functions a shared object defines and exports, once
+/// each even when several symbol versions are exported.
+fn exported_functions(path: &str) -> Result<Vec<String>, goblin::error::Error> {
+    let bytes = fs::read(path)?;
+    let elf = Elf::parse(&bytes)?;
+
+    let mut names: Vec<String> = elf
+        .dynsyms
+        .iter()
+        .filter(|sym| !sym.is_import())
+        .filter(|sym| matches!(sym.st_type(), STT_FUNC | STT_GNU_IFUNC))
+        .filter(|sym| matches!(sym.st_bind(), STB_GLOBAL | STB_WEAK))
+        .filter(|sym| matches!(sym.st_visibility(), STV_DEFAULT | STV_PROTECTED))
+        .filter_map(|sym| elf.dynstrtab.get_at(sym.st_name))
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect();
+
+    names.sort();
+    names.dedup();
+
+    Ok(names)
+}
@@ -0,0 +1,6 @@
+pub mod check;
+pub mod coverage;
+pub mod exports;
+pub mod portability;
+pub mod scaffold;
+pub mod verify;
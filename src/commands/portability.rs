@@ -0,0 +1,164 @@
+use crate::{c_parser, discover_document, vocabulary::Vocabulary, Document};
+use std::{fs, process};
+
+/// A documented symbol or header a source file uses.
+struct Usage<'a> {
+    name: String,
+    header: &'a str,
+    os_affinity: &'a [String],
+}
+
+/// `portability <corpus path> <source file>...`
+///
+/// Scans C source files for included headers and identifiers, matches them
+/// against the corpus and reports which operating systems every one of them
+/// supports, plus the symbols that rule out each other OS.
+pub(crate) fn run(args: &[String]) {
+    if args.len() < 2 {
+        eprintln!("Usage: capibara-processor portability <corpus path> <source file>...");
+        process::exit(2);
+    }
+
+    let filepath = &args[0];
+    println!("Filepath:\t\t{}", filepath);
+    println!("Source Files:\t\t{}", args[1..].join(", "));
+
+    let document = discover_document(filepath, "");
+    let mut usages: Vec<Usage> = Vec::new();
+
+    for source_path in args[1..].iter() {
+        match fs::read_to_string(source_path) {
+            Ok(source) => {
+                for usage in find_usages(&document, &source) {
+                    if !usages
+                        .iter()
+                        .any(|u| u.name == usage.name && u.header == usage.header)
+                    {
+                        usages.push(usage);
+                    }
+                }
+            }
+            Err(error) => {
+                eprintln!("Portability Error ({}): {}", source_path, error);
+                process::exit(1);
+            }
+        }
+    }
+
+    let os_vocabulary = Vocabulary::load_os(filepath);
+    let all_os: Vec<&str> = os_vocabulary
+        .names()
+        .into_iter()
+        .filter(|os| {
+            document
+                .headers
+                .iter()
+                .any(|h| h.os_affinity.iter().any(|a| a == os))
+        })
+        .collect();
+
+    println!("\nUses {} documented symbol(s):", usages.len());
+    for usage in usages.iter() {
+        println!(
+            "  {} ({}): {}",
+            usage.name,
+            usage.header,
+            usage.os_affinity.join(", ")
+        );
+    }
+
+    let supported: Vec<&str> = all_os
+        .iter()
+        .filter(|os| {
+            usages
+                .iter()
+                .all(|u| u.os_affinity.iter().any(|a| a == *os))
+        })
+        .copied()
+        .collect();
+
+    println!(
+        "\nPortable to: {}",
+        if supported.is_empty() {
+            String::from("none")
+        } else {
+            supported.join(", ")
+        }
+    );
+
+    for os in all_os.iter().filter(|os| !supported.contains(os)) {
+        let blockers: Vec<&str> = usages
+            .iter()
+            .filter(|u| !u.os_affinity.iter().any(|a| a == *os))
+            .map(|u| u.name.as_str())
+            .collect();
+        println!("Blocked on {} by: {}", os, blockers.join(", "));
+    }
+}
+
+fn find_usages<'a>(document: &'a Document, source: &str) -> Vec<Usage<'a>> {
+    let mut usages = Vec::new();
+
+    for include in c_parser::parse(source).includes.iter() {
+        if let Some(header) = document.headers.iter().find(|h| h.name.as_str() == include) {
+            usages.push(Usage {
+                name: format!("<{}>", header.name),
+                header: &header.name,
+                os_affinity: &header.os_affinity,
+            });
+        }
+    }
+
+    let tokens = c_parser::source_tokens(source);
+
+    for (i, token) in tokens.iter().enumerate() {
+        let previous = if i > 0 { tokens[i - 1].as_str() } else { "" };
+
+        // Member accesses (`t.time`, `t->time`) name fields, not symbols.
+        if previous == "." || previous == ">" {
+            continue;
+        }
+
+        let mut found = |name: String, header: &'a str, os_affinity: &'a [String]| {
+            usages.push(Usage {
+                name,
+                header,
+                os_affinity,
+            });
+        };
+
+        match previous {
+            "struct" => {
+                for _struct in document.structs.iter().filter(|s| *s.name == *token) {
+                    found(
+                        format!("struct {}", token),
+                        &_struct.header.name,
+                        &_struct.os_affinity,
+                    );
+                }
+            }
+            "enum" => {
+                for _enum in document.enums.iter().filter(|e| *e.name == *token) {
+                    found(
+                        format!("enum {}", token),
+                        &_enum.header.name,
+                        &_enum.os_affinity,
+                    );
+                }
+            }
+            _ => {
+                for function in document.functions.iter().filter(|f| *f.name == *token) {
+                    found(token.clone(), &function.header.name, &function.os_affinity);
+                }
+                for _macro in document.macros.iter().filter(|m| *m.name == *token) {
+                    found(token.clone(), &_macro.header.name, &_macro.os_affinity);
+                }
+                for typedef in document.typedefs.iter().filter(|t| *t.name == *token) {
+                    found(token.clone(), &typedef.header.name, &typedef.os_affinity);
+                }
+            }
+        }
+    }
+
+    usages
+}
@@ -0,0 +1,279 @@
+use super::check::TODO_MARKER;
+use crate::{
+    c_parser::{self, CHeader, CTypedef},
+    definitions::{
+        _macro::{MacroFunction, MacroKind, MacroObject, TypelessParameter, YamlMacro},
+        _struct::{Field, YamlStruct},
+        enumeration::{Variant, YamlEnumeration},
+        function::{Parameter, Return, YamlFunction},
+        header::YamlHeader,
+        typedef::YamlTypedef,
+    },
+};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// `scaffold <corpus path> <header file> [<header ref>]`
+///
+/// Creates `<corpus path>/<header ref>` with a `meta.yaml` and stub files for
+/// every public function, macro, struct, enum and typedef the header
+/// declares. Names, types, parameters, fields and variants are filled in;
+/// prose is left as `TODO` for `check` to flag. Existing files are never
+/// overwritten.
+pub(crate) fn run(args: &[String]) {
+    if args.len() < 2 {
+        eprintln!("Usage: capibara-processor scaffold <corpus path> <header file> [<header ref>]");
+        process::exit(2);
+    }
+
+    let header_path = Path::new(&args[1]);
+    let header_ref = match args.get(2) {
+        Some(header_ref) => header_ref.trim_end_matches(".h").to_string(),
+        None => header_path
+            .file_stem()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string(),
+    };
+
+    println!("Header:\t\t\t{}", header_path.display());
+    println!("Header Ref:\t\t{}", header_ref);
+
+    let c_header = match c_parser::parse_file(header_path) {
+        Ok(c_header) => c_header,
+        Err(error) => {
+            eprintln!("Scaffold Error: {:?}", error);
+            process::exit(1);
+        }
+    };
+
+    let directory = PathBuf::from(&args[0]).join(&header_ref);
+
+    if let Err(error) = fs::create_dir_all(&directory) {
+        eprintln!("Scaffold Error: {:?}", error);
+        process::exit(1);
+    }
+
+    let mut written = 0;
+
+    for (file_name, yaml) in stubs(&c_header, &header_ref) {
+        let path = directory.join(&file_name);
+
+        if path.exists() {
+            println!("Skipped existing {}", path.display());
+            continue;
+        }
+
+        match fs::write(&path, yaml) {
+            Ok(()) => written += 1,
+            Err(error) => eprintln!("Scaffold Error ({:?}): {:?}", path, error),
+        }
+    }
+
+    println!("Wrote {} file(s) to {}", written, directory.display());
+}
+
+/// File name and YAML contents for every stub, `meta.yaml` first.
+fn stubs(c_header: &CHeader, header_ref: &str) -> Vec<(String, String)> {
+    let mut stubs = vec![(
+        String::from("meta.yaml"),
+        to_yaml(&YamlHeader {
+            summary: Box::new(todo()),
+            os_affinity: None,
+            standards: None,
+            link: None,
+        }),
+    )];
+
+    for function in c_header.functions.iter().filter(|f| is_public(&f.name)) {
+        let yaml = YamlFunction {
+            summary: Box::new(todo()),
+            returns: Return {
+                _type: Box::new(function.returns.clone()),
+                description: Box::new(todo()),
+                convention: None,
+            },
+            parameters: function
+                .parameters
+                .iter()
+                .enumerate()
+                .map(|(index, parameter)| Parameter {
+                    name: Box::new(parameter_name(&parameter.name, index)),
+                    _type: Box::new(parameter._type.clone()),
+                    description: Box::new(todo()),
+                })
+                .collect(),
+            description: Box::new(todo()),
+            examples: Vec::new(),
+            associated: Vec::new(),
+            os_affinity: None,
+            arch_affinity: None,
+            standards: None,
+            deprecated: None,
+            availability: None,
+            feature_test_macros: Vec::new(),
+            link: None,
+            attributes: None,
+            errors: Vec::new(),
+        };
+        stubs.push((format!("fn-{}.yaml", function.name), to_yaml(&yaml)));
+    }
+
+    // Macros that redirect a declared function (`fopen` to `fopen64`) are
+    // already covered by the function stub.
+    for _macro in c_header
+        .macros
+        .iter()
+        .filter(|m| is_public(&m.name) && c_header.function(&m.name).is_none())
+    {
+        let kind = match &_macro.parameters {
+            Some(parameters) => MacroKind::Function(MacroFunction {
+                returns: Return {
+                    _type: Box::new(todo()),
+                    description: Box::new(todo()),
+                    convention: None,
+                },
+                parameters: parameters
+                    .iter()
+                    .map(|name| TypelessParameter {
+                        name: Box::new(name.clone()),
+                        description: Box::new(todo()),
+                    })
+                    .collect(),
+                examples: Vec::new(),
+                errors: Vec::new(),
+                attributes: None,
+            }),
+            None => MacroKind::Object(MacroObject {}),
+        };
+        let yaml = YamlMacro {
+            summary: Box::new(todo()),
+            kind,
+            description: Box::new(todo()),
+            associated: Vec::new(),
+            os_affinity: None,
+            arch_affinity: None,
+            standards: None,
+            deprecated: None,
+            availability: None,
+            feature_test_macros: Vec::new(),
+        };
+        stubs.push((format!("mo-{}.yaml", _macro.name), to_yaml(&yaml)));
+    }
+
+    for _struct in c_header.structs.iter().filter(|s| is_public(&s.name)) {
+        let yaml = YamlStruct {
+            summary: Box::new(todo()),
+            fields: _struct
+                .fields
+                .iter()
+                .filter(|f| !f.name.is_empty())
+                .map(|field| Field {
+                    name: Box::new(field.name.clone()),
+                    _type: Box::new(field._type.clone()),
+                    description: Box::new(todo()),
+                })
+                .collect(),
+            description: Box::new(todo()),
+            os_affinity: None,
+            arch_affinity: None,
+            standards: None,
+            deprecated: None,
+            availability: None,
+            feature_test_macros: Vec::new(),
+        };
+        stubs.push((format!("st-{}.yaml", _struct.name), to_yaml(&yaml)));
+    }
+
+    for _enum in c_header.enums.iter().filter(|e| is_public(&e.name)) {
+        let yaml = YamlEnumeration {
+            summary: Box::new(todo()),
+            variants: _enum
+                .variants
+                .iter()
+                .map(|variant| Variant {
+                    name: Box::new(variant.clone()),
+                    description: Box::new(todo()),
+                })
+                .collect(),
+            description: Box::new(todo()),
+            os_affinity: None,
+            arch_affinity: None,
+            standards: None,
+            deprecated: None,
+            availability: None,
+        };
+        stubs.push((format!("em-{}.yaml", _enum.name), to_yaml(&yaml)));
+    }
+
+    for typedef in c_header.typedefs.iter().filter(|t| is_public(&t.name)) {
+        let yaml = YamlTypedef {
+            summary: Box::new(todo()),
+            _type: Box::new(typedef._type.clone()),
+            associated_ref: Box::new(associated_ref(c_header, header_ref, typedef)),
+            description: Box::new(todo()),
+            os_affinity: None,
+            arch_affinity: None,
+            standards: None,
+            deprecated: None,
+            availability: None,
+            feature_test_macros: Vec::new(),
+        };
+        stubs.push((format!("tf-{}.yaml", typedef.name), to_yaml(&yaml)));
+    }
+
+    stubs
+}
+
+/// Names in the implementation's reserved `_` namespace aren't documented.
+fn is_public(name: &str) -> bool {
+    !name.starts_with('_')
+}
+
+fn todo() -> String {
+    TODO_MARKER.to_string()
+}
+
+/// Unnamed prototype parameters get a numbered placeholder name.
+fn parameter_name(name: &str, index: usize) -> String {
+    if name.is_empty() {
+        format!("{}{}", TODO_MARKER, index + 1)
+    } else {
+        c_parser::public_name(name).to_string()
+    }
+}
+
+/// `header/name` of the struct or enum a typedef aliases when this header
+/// declares it, otherwise empty.
+fn associated_ref(c_header: &CHeader, header_ref: &str, typedef: &CTypedef) -> String {
+    let Some((keyword, name)) = typedef._type.split_once(' ') else {
+        return String::new();
+    };
+    // `typedef struct { ... } div_t;` is recorded under the alias.
+    let name = if name == "{ ... }" {
+        &typedef.name
+    } else {
+        name
+    };
+
+    let declared = match keyword {
+        "struct" | "union" => c_header._struct(name).is_some(),
+        "enum" => c_header._enum(name).is_some(),
+        _ => false,
+    };
+
+    if declared && is_public(name) {
+        format!("{}/{}", header_ref, name)
+    } else {
+        String::new()
+    }
+}
+
+fn to_yaml<T: Serialize>(value: &T) -> String {
+    serde_yaml::to_string(value).unwrap()
+}
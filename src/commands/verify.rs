@@ -0,0 +1,183 @@
+use crate::{
+    c_parser::{self, normalize_type, public_name, CHeader},
+    discover_document, Document,
+};
+use std::{path::PathBuf, process};
+
+/// `verify <corpus path> <include dir>...`
+///
+/// Parses the real header for every documented header, following includes
+/// across the given include directories, and reports
+/// prototypes, struct fields and enum variants that disagree with the
+/// corpus. Exits non-zero when anything is reported.
+pub(crate) fn run(args: &[String]) {
+    if args.len() < 2 {
+        eprintln!("Usage: capibara-processor verify <corpus path> <include dir>...");
+        process::exit(2);
+    }
+
+    let filepath = &args[0];
+    println!("Filepath:\t\t{}", filepath);
+    let include_dirs: Vec<PathBuf> = args[1..].iter().map(PathBuf::from).collect();
+    println!("Include Directories:\t{}", args[1..].join(", "));
+
+    let document = discover_document(filepath, "");
+    let mut mismatch_count = 0;
+
+    for header in document.headers.iter() {
+        let Some(header_path) = c_parser::find_header(&header.name, &include_dirs) else {
+            println!("\n{}: not found in the include directories", header.name);
+            mismatch_count += 1;
+            continue;
+        };
+
+        let c_header = match c_parser::parse_file_with_includes(&header_path, &include_dirs) {
+            Ok(c_header) => c_header,
+            Err(error) => {
+                println!(
+                    "\n{}: could not read {:?}: {}",
+                    header.name, header_path, error
+                );
+                mismatch_count += 1;
+                continue;
+            }
+        };
+
+        let mismatches = compare_header(&document, &header._ref, &c_header);
+
+        if !mismatches.is_empty() {
+            println!("\n{} ({})", header.name, header_path.display());
+            for mismatch in mismatches.iter() {
+                println!("  {}", mismatch);
+            }
+            mismatch_count += mismatches.len();
+        }
+    }
+
+    println!(
+        "\n{} mismatch(es) across {} header(s)",
+        mismatch_count,
+        document.headers.len()
+    );
+
+    if mismatch_count > 0 {
+        process::exit(1);
+    }
+}
+
+fn compare_header(document: &Document, header_ref: &str, c_header: &CHeader) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    for function in document
+        .functions
+        .iter()
+        .filter(|f| f.header._ref.as_str() == header_ref)
+    {
+        let Some(declared) = c_header.function(&function.name) else {
+            mismatches.push(format!("function {}: not declared", function.name));
+            continue;
+        };
+
+        if normalize_type(&function.returns._type) != normalize_type(&declared.returns) {
+            mismatches.push(format!(
+                "function {}: returns `{}`, header declares `{}`",
+                function.name, function.returns._type, declared.returns
+            ));
+        }
+
+        if function.parameters.len() != declared.parameters.len() {
+            mismatches.push(format!(
+                "function {}: {} parameter(s) documented, header declares {}",
+                function.name,
+                function.parameters.len(),
+                declared.parameters.len()
+            ));
+            continue;
+        }
+
+        for (index, (documented, actual)) in function
+            .parameters
+            .iter()
+            .zip(declared.parameters.iter())
+            .enumerate()
+        {
+            if normalize_type(&documented._type) != normalize_type(&actual._type) {
+                mismatches.push(format!(
+                    "function {}: parameter {} `{}` is `{}`, header declares `{}`",
+                    function.name,
+                    index + 1,
+                    documented.name,
+                    documented._type,
+                    actual._type
+                ));
+            }
+        }
+    }
+
+    for _struct in document
+        .structs
+        .iter()
+        .filter(|s| s.header._ref.as_str() == header_ref)
+    {
+        let Some(declared) = c_header._struct(&_struct.name) else {
+            mismatches.push(format!("struct {}: not declared", _struct.name));
+            continue;
+        };
+
+        for field in _struct.fields.iter() {
+            match declared
+                .fields
+                .iter()
+                .find(|f| public_name(&f.name) == field.name.as_str())
+            {
+                Some(actual) => {
+                    if normalize_type(&field._type) != normalize_type(&actual._type) {
+                        mismatches.push(format!(
+                            "struct {}: field `{}` is `{}`, header declares `{}`",
+                            _struct.name, field.name, field._type, actual._type
+                        ));
+                    }
+                }
+                None => mismatches.push(format!(
+                    "struct {}: field `{}` is not declared",
+                    _struct.name, field.name
+                )),
+            }
+        }
+
+        for actual in declared.fields.iter() {
+            let name = public_name(&actual.name);
+            if !name.is_empty() && !_struct.fields.iter().any(|f| f.name.as_str() == name) {
+                mismatches.push(format!("struct {}: missing field `{}`", _struct.name, name));
+            }
+        }
+    }
+
+    for _enum in document
+        .enums
+        .iter()
+        .filter(|e| e.header._ref.as_str() == header_ref)
+    {
+        let Some(declared) = c_header._enum(&_enum.name) else {
+            mismatches.push(format!("enum {}: not declared", _enum.name));
+            continue;
+        };
+
+        for variant in _enum.variants.iter() {
+            if !declared.variants.iter().any(|v| v == variant.name.as_str()) {
+                mismatches.push(format!(
+                    "enum {}: extra variant `{}` is not declared",
+                    _enum.name, variant.name
+                ));
+            }
+        }
+
+        for actual in declared.variants.iter() {
+            if !_enum.variants.iter().any(|v| v.name.as_str() == actual) {
+                mismatches.push(format!("enum {}: missing variant `{}`", _enum.name, actual));
+            }
+        }
+    }
+
+    mismatches
+}
@@ -1,4 +1,8 @@
-use super::{function::Return, header::HeaderSummary};
+use super::{
+    attributes::Attributes, availability::Availability, deprecated::Deprecated,
+    error_condition::ErrorCondition, feature_test_macro::FeatureTestMacro, function::Return,
+    header::HeaderSummary, standard::Standard,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -6,7 +10,20 @@ pub(crate) struct YamlMacro {
     pub summary: Box<String>,
     pub kind: MacroKind,
     pub description: Box<String>,
-    pub os_affinity: Vec<String>,
+    #[serde(default)]
+    pub associated: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os_affinity: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch_affinity: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub standards: Option<Vec<Standard>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecated>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<Vec<Availability>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feature_test_macros: Vec<FeatureTestMacro>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -16,14 +33,24 @@ pub(crate) struct Macro {
     pub summary: Box<String>,
     pub kind: MacroKind,
     pub description: Box<String>,
+    pub associated: Vec<String>,
     pub os_affinity: Vec<String>,
+    pub arch_affinity: Vec<String>,
+    pub standards: Vec<Standard>,
+    pub deprecated: Option<Deprecated>,
+    pub availability: Vec<Availability>,
+    pub feature_test_macros: Vec<FeatureTestMacro>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub(crate) struct MacroFunction {
     pub returns: Return,
     pub parameters: Vec<TypelessParameter>,
-    pub examples: Vec<Example>
+    pub examples: Vec<Example>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ErrorCondition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<Box<Attributes>>
 }
 
 #[derive(Serialize, Deserialize)]
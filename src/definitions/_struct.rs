@@ -1,4 +1,7 @@
-use super::header::HeaderSummary;
+use super::{
+    availability::Availability, deprecated::Deprecated, feature_test_macro::FeatureTestMacro,
+    header::HeaderSummary, standard::Standard,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -6,7 +9,18 @@ pub(crate) struct YamlStruct {
     pub summary: Box<String>,
     pub fields: Vec<Field>,
     pub description: Box<String>,
-    pub os_affinity: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os_affinity: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch_affinity: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub standards: Option<Vec<Standard>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecated>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<Vec<Availability>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feature_test_macros: Vec<FeatureTestMacro>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
@@ -17,6 +31,11 @@ pub(crate) struct Struct {
     pub fields: Vec<Field>,
     pub description: Box<String>,
     pub os_affinity: Vec<String>,
+    pub arch_affinity: Vec<String>,
+    pub standards: Vec<Standard>,
+    pub deprecated: Option<Deprecated>,
+    pub availability: Vec<Availability>,
+    pub feature_test_macros: Vec<FeatureTestMacro>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
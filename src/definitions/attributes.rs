@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct Attributes {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_safety: Option<Safety>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub async_signal_safety: Option<Safety>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub async_cancel_safety: Option<Safety>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancellation_point: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct Safety {
+    pub level: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reasons: Vec<String>,
+}
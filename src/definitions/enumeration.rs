@@ -1,4 +1,6 @@
-use super::header::HeaderSummary;
+use super::{
+    availability::Availability, deprecated::Deprecated, header::HeaderSummary, standard::Standard,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -6,7 +8,16 @@ pub(crate) struct YamlEnumeration {
     pub summary: Box<String>,
     pub variants: Vec<Variant>,
     pub description: Box<String>,
-    pub os_affinity: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os_affinity: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch_affinity: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub standards: Option<Vec<Standard>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecated>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<Vec<Availability>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
@@ -17,6 +28,10 @@ pub(crate) struct Enumeration {
     pub variants: Vec<Variant>,
     pub description: Box<String>,
     pub os_affinity: Vec<String>,
+    pub arch_affinity: Vec<String>,
+    pub standards: Vec<Standard>,
+    pub deprecated: Option<Deprecated>,
+    pub availability: Vec<Availability>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
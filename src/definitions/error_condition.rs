@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct ErrorCondition {
+    pub errno: String,
+    pub condition: String,
+    /// Reference of the macro documenting `errno`, filled in on discovery.
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub _ref: Option<String>,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct FeatureTestMacro {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Reference of the macro documenting `name`, filled in on discovery.
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub _ref: Option<String>,
+}
@@ -1,4 +1,8 @@
-use super::header::HeaderSummary;
+use super::{
+    attributes::Attributes, availability::Availability, deprecated::Deprecated,
+    error_condition::ErrorCondition, feature_test_macro::FeatureTestMacro, header::HeaderSummary,
+    link::Link, standard::Standard,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -9,7 +13,24 @@ pub(crate) struct YamlFunction {
     pub description: Box<String>,
     pub examples: Vec<Example>,
     pub associated: Vec<String>,
-    pub os_affinity: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os_affinity: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch_affinity: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub standards: Option<Vec<Standard>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecated>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<Vec<Availability>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feature_test_macros: Vec<FeatureTestMacro>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<Link>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<Attributes>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ErrorCondition>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -23,6 +44,14 @@ pub(crate) struct Function {
     pub examples: Vec<Example>,
     pub associated: Vec<String>,
     pub os_affinity: Vec<String>,
+    pub arch_affinity: Vec<String>,
+    pub standards: Vec<Standard>,
+    pub deprecated: Option<Deprecated>,
+    pub availability: Vec<Availability>,
+    pub feature_test_macros: Vec<FeatureTestMacro>,
+    pub link: Option<Link>,
+    pub attributes: Option<Attributes>,
+    pub errors: Vec<ErrorCondition>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -44,4 +73,16 @@ pub(crate) struct Return {
     #[serde(rename = "type")]
     pub _type: Box<String>,
     pub description: Box<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub convention: Option<ReturnConvention>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct ReturnConvention {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+    #[serde(default)]
+    pub sets_errno: bool,
 }
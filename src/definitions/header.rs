@@ -1,8 +1,15 @@
+use super::{link::Link, standard::Standard};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub(crate) struct YamlHeader {
     pub summary: Box<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os_affinity: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub standards: Option<Vec<Standard>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<Link>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -12,6 +19,9 @@ pub(crate) struct Header {
     pub name: Box<String>,
     pub summary: Box<String>,
     pub os_affinity: Vec<String>,
+    pub arch_affinity: Vec<String>,
+    pub standards: Vec<Standard>,
+    pub link: Option<Link>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
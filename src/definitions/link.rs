@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct Link {
+    #[serde(flatten)]
+    pub libraries: LinkLibraries,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub os_overrides: Vec<OsLink>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct LinkLibraries {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub libraries: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pkg_config: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct OsLink {
+    pub os: Vec<String>,
+    #[serde(flatten)]
+    pub libraries: LinkLibraries,
+}
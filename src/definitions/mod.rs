@@ -1,6 +1,13 @@
 pub mod _macro;
 pub mod _struct;
+pub mod attributes;
+pub mod availability;
+pub mod deprecated;
 pub mod enumeration;
+pub mod error_condition;
+pub mod feature_test_macro;
 pub mod function;
 pub mod header;
+pub mod link;
+pub mod standard;
 pub mod typedef;
@@ -1,4 +1,7 @@
-use super::{_struct::Struct, enumeration::Enumeration, header::HeaderSummary};
+use super::{
+    _struct::Struct, availability::Availability, deprecated::Deprecated, enumeration::Enumeration,
+    feature_test_macro::FeatureTestMacro, header::HeaderSummary, standard::Standard,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -8,7 +11,18 @@ pub(crate) struct YamlTypedef {
     pub _type: Box<String>,
     pub associated_ref: Box<String>,
     pub description: Box<String>,
-    pub os_affinity: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os_affinity: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch_affinity: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub standards: Option<Vec<Standard>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<Deprecated>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<Vec<Availability>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feature_test_macros: Vec<FeatureTestMacro>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -21,6 +35,11 @@ pub(crate) struct Typedef {
     pub associated_ref: TypedefRef,
     pub description: Box<String>,
     pub os_affinity: Vec<String>,
+    pub arch_affinity: Vec<String>,
+    pub standards: Vec<Standard>,
+    pub deprecated: Option<Deprecated>,
+    pub availability: Vec<Availability>,
+    pub feature_test_macros: Vec<FeatureTestMacro>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq)]
@@ -0,0 +1,122 @@
+//! How a function reports failure: its `errors`, each naming an errno value
+//! and linked to the macro documenting it when the corpus has one, and the
+//! `convention` of its return value.
+//!
+//! ```yaml
+//! returns:
+//!   type: int
+//!   description: Zero on success, -1 on error.
+//!   convention:
+//!     success: "0"
+//!     failure: "-1"
+//!     sets_errno: true
+//! errors:
+//!   - errno: EINVAL
+//!     condition: The clock ID is not supported.
+//! ```
+
+use crate::definitions::{_macro::Macro, error_condition::ErrorCondition, function::Return};
+
+/// Points every error at the macro documenting its errno value. Errors whose
+/// value no macro documents are kept with no reference.
+pub(crate) fn resolve(errors: Vec<ErrorCondition>, macros: &[Macro]) -> Vec<ErrorCondition> {
+    errors
+        .into_iter()
+        .map(|error| {
+            let errno = error.errno.trim();
+            let _ref = macros
+                .iter()
+                .find(|m| m.name.as_str() == errno)
+                .map(|m| format!("{}/{}", m.header._ref, m.name));
+
+            ErrorCondition {
+                errno: errno.to_string(),
+                condition: error.condition,
+                _ref,
+            }
+        })
+        .collect()
+}
+
+/// A convention that sets errno has to say what is returned when it does.
+pub(crate) fn check_convention(returns: &Return) -> Result<(), String> {
+    match &returns.convention {
+        Some(convention) if convention.sets_errno && convention.failure.is_none() => Err(
+            String::from("return convention sets errno without a failure value"),
+        ),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::object_macro;
+    use serde_json::json;
+
+    fn errno_macro(name: &str) -> Macro {
+        serde_json::from_value(object_macro("errno", name, &[])).unwrap()
+    }
+
+    fn error(errno: &str) -> ErrorCondition {
+        ErrorCondition {
+            errno: errno.to_string(),
+            condition: String::from("condition"),
+            _ref: None,
+        }
+    }
+
+    fn returns(convention: serde_json::Value) -> Return {
+        serde_json::from_value(json!({
+            "type": "int",
+            "description": "",
+            "convention": convention,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn resolve_links_errors_to_their_macros() {
+        let macros = vec![errno_macro("EINVAL"), errno_macro("ENOMEM")];
+
+        let errors = resolve(vec![error(" ENOMEM "), error("EINVAL")], &macros);
+
+        let resolved: Vec<(&str, Option<&str>)> = errors
+            .iter()
+            .map(|e| (e.errno.as_str(), e._ref.as_deref()))
+            .collect();
+        assert_eq!(
+            resolved,
+            vec![
+                ("ENOMEM", Some("errno/ENOMEM")),
+                ("EINVAL", Some("errno/EINVAL")),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_keeps_undocumented_errno_values() {
+        let macros = vec![errno_macro("EINVAL")];
+
+        let errors = resolve(vec![error("EOVERFLOW"), error("EINVAL")], &macros);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].errno, "EOVERFLOW");
+        assert_eq!(errors[0].condition, "condition");
+        assert_eq!(errors[0]._ref, None);
+        assert_eq!(errors[1]._ref.as_deref(), Some("errno/EINVAL"));
+    }
+
+    #[test]
+    fn check_convention_requires_a_failure_value_when_errno_is_set() {
+        assert!(check_convention(&returns(json!(null))).is_ok());
+        assert!(check_convention(&returns(json!({ "failure": "-1", "sets_errno": true }))).is_ok());
+        assert!(check_convention(&returns(json!({ "success": "0" }))).is_ok());
+        assert_eq!(
+            check_convention(&returns(json!({ "success": "0", "sets_errno": true }))),
+            Err(String::from(
+                "return convention sets errno without a failure value"
+            ))
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use super::{
+    deprecation_summary, enum_definition, function_signature, referenced_types, split_ref,
+    struct_definition, typedef_definition,
+};
+use crate::{
+    definitions::{deprecated::Deprecated, header::Header, typedef::Typedef},
+    Document,
+};
+use std::{fs, io, path::Path};
+
+const CHECK_FILE: &str = "capibara-check.c";
+
+/// Writes a C header per documented header to `output_path`, declaring its
+/// enums, structs, typedefs and function prototypes, plus a
+/// `capibara-check.c` that includes all of them.
+///
+/// The headers only depend on each other, so a syntax check such as
+/// `cc -fsyntax-only -fno-builtin -I <output_path> <output_path>/capibara-check.c`
+/// reports type strings in the YAML that don't declare.
+pub(crate) fn export(document: &Document, output_path: &str) -> io::Result<()> {
+    let root = Path::new(output_path);
+    fs::create_dir_all(root)?;
+
+    let mut check = String::from("/* Generated by capibara-processor. */\n\n");
+
+    for header in document.headers.iter() {
+        let path = root.join(&*header.name);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&path, render_header(document, header))?;
+        check += &format!("#include \"{}\"\n", header.name);
+    }
+
+    fs::write(root.join(CHECK_FILE), check)
+}
+
+fn render_header(document: &Document, header: &Header) -> String {
+    let guard = format!(
+        "CAPIBARA_{}",
+        header
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            })
+            .collect::<String>()
+    );
+
+    let mut out = format!(
+        "/* {} - {} */\n/* Generated by capibara-processor. */\n\n#ifndef {}\n#define {}\n\n",
+        header.name,
+        header
+            .summary
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" "),
+        guard,
+        guard
+    );
+
+    let includes = dependencies(document, header);
+    for include in includes.iter() {
+        out += &format!("#include \"{}\"\n", include);
+    }
+    if !includes.is_empty() {
+        out += "\n";
+    }
+
+    let structs: Vec<_> = document
+        .structs
+        .iter()
+        .filter(|s| s.header._ref == header._ref)
+        .collect();
+    // An empty enum isn't valid C; a struct without fields stays a forward
+    // declaration.
+    let enums: Vec<_> = document
+        .enums
+        .iter()
+        .filter(|e| e.header._ref == header._ref && !e.variants.is_empty())
+        .collect();
+    let typedefs = dependency_order(
+        document
+            .typedefs
+            .iter()
+            .filter(|t| t.header._ref == header._ref)
+            .collect(),
+    );
+    let functions: Vec<_> = document
+        .functions
+        .iter()
+        .filter(|f| f.header._ref == header._ref)
+        .collect();
+
+    // Structs are forward declared so typedefs can alias them before their
+    // definitions, which may in turn use those typedefs.
+    for _struct in structs.iter() {
+        out += &format!("struct {};\n", _struct.name);
+    }
+    if !structs.is_empty() {
+        out += "\n";
+    }
+
+    for _enum in enums.iter() {
+        out += &deprecation_comment(&_enum.deprecated);
+        out += &format!("{}\n\n", enum_definition(_enum));
+    }
+
+    for typedef in typedefs.iter() {
+        out += &deprecation_comment(&typedef.deprecated);
+        out += &format!("{}\n", typedef_definition(typedef));
+    }
+    if !typedefs.is_empty() {
+        out += "\n";
+    }
+
+    for _struct in structs.iter().filter(|s| !s.fields.is_empty()) {
+        out += &deprecation_comment(&_struct.deprecated);
+        out += &format!("{}\n\n", struct_definition(_struct));
+    }
+
+    for function in functions.iter() {
+        out += &deprecation_comment(&function.deprecated);
+        out += &format!("{}\n", function_signature(function));
+    }
+    if !functions.is_empty() {
+        out += "\n";
+    }
+
+    out += &format!("#endif /* {} */\n", guard);
+
+    out
+}
+
+/// Orders a header's typedefs so each follows the typedefs its type names;
+/// otherwise they keep their document order. A cycle is broken at its first
+/// typedef.
+fn dependency_order(mut pending: Vec<&Typedef>) -> Vec<&Typedef> {
+    let mut ordered = Vec::with_capacity(pending.len());
+
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|typedef| {
+                typedef_names(&typedef._type).iter().all(|name| {
+                    !pending
+                        .iter()
+                        .any(|other| other.name.as_str() == *name && other.name != typedef.name)
+                })
+            })
+            .unwrap_or(0);
+
+        ordered.push(pending.remove(ready));
+    }
+
+    ordered
+}
+
+/// Identifiers in a type string that can name a typedef, i.e. those not
+/// following a `struct`, `union` or `enum` tag keyword.
+fn typedef_names(_type: &str) -> Vec<&str> {
+    let words: Vec<&str> = _type
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    words
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i == 0 || !["struct", "union", "enum"].contains(&words[i - 1]))
+        .map(|(_, word)| *word)
+        .collect()
+}
+
+/// A comment flagging a deprecated declaration, on one line.
+fn deprecation_comment(deprecated: &Option<Deprecated>) -> String {
+    let deprecated = match deprecated {
+        Some(deprecated) => deprecated,
+        None => return String::new(),
+    };
+
+    let mut text = deprecation_summary(deprecated);
+
+    if let Some(replacement) = &deprecated.replacement {
+        let name = split_ref(replacement).map_or(replacement.as_str(), |(_, name)| name);
+        text += &format!(" Use {} instead.", name);
+    }
+
+    format!(
+        "/* {} */\n",
+        text.split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .replace("*/", "* /")
+    )
+}
+
+/// Other documented headers whose structs, enums or typedefs are named in
+/// this header's type strings.
+fn dependencies(document: &Document, header: &Header) -> Vec<String> {
+    let mut types = Vec::new();
+
+    for function in document
+        .functions
+        .iter()
+        .filter(|f| f.header._ref == header._ref)
+    {
+        types.push(function.returns._type.to_string());
+        for parameter in function.parameters.iter() {
+            types.push(parameter._type.to_string());
+        }
+    }
+
+    for _struct in document
+        .structs
+        .iter()
+        .filter(|s| s.header._ref == header._ref)
+    {
+        for field in _struct.fields.iter() {
+            types.push(field._type.to_string());
+        }
+    }
+
+    for typedef in document
+        .typedefs
+        .iter()
+        .filter(|t| t.header._ref == header._ref)
+    {
+        types.push(typedef._type.to_string());
+    }
+
+    let mut includes: Vec<String> = Vec::new();
+
+    for _type in types.iter() {
+        for referenced in referenced_types(document, _type) {
+            if referenced.header._ref != header._ref && !includes.contains(&referenced.header.name)
+            {
+                includes.push(referenced.header.name.to_string());
+            }
+        }
+    }
+
+    includes.sort();
+    includes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{_struct, document, enumeration, function, header, temp_dir, typedef};
+    use serde_json::json;
+
+    fn time() -> Document {
+        let mut timespec = _struct("time", "timespec", &["linux"]);
+        timespec["fields"] = json!([{ "name": "tv_sec", "type": "time_t", "description": "" }]);
+        let mut clock = function("time", "clock_gettime", &["linux"]);
+        clock["parameters"] = json!([
+            { "name": "clock", "type": "clockid_t", "description": "" },
+            { "name": "tp", "type": "struct timespec *", "description": "" }
+        ]);
+        let mut now = function("app", "now", &["linux"]);
+        now["returns"] = json!({ "type": "time_t", "description": "" });
+
+        let mut document = document(
+            vec![header("app", &["linux"]), header("time", &["linux"])],
+            vec![now, clock],
+            vec![],
+            vec![_struct("time", "opaque", &["linux"]), timespec],
+            vec![
+                typedef("time", "clockid_t", "clock_kind_t", &["linux"]),
+                typedef("time", "clock_kind_t", "enum clock_kind", &["linux"]),
+                typedef("time", "time_t", "long", &["linux"]),
+            ],
+        );
+        document.enums = vec![
+            serde_json::from_value(enumeration("time", "clock_kind", &["CLOCK_A"], &["linux"]))
+                .unwrap(),
+            serde_json::from_value(enumeration("time", "empty", &[], &["linux"])).unwrap(),
+        ];
+        document
+    }
+
+    #[test]
+    fn typedefs_follow_the_typedefs_they_name() {
+        let document = time();
+        let out = render_header(&document, &document.headers[1]);
+
+        assert!(out.contains(
+            "typedef enum clock_kind clock_kind_t;\ntypedef clock_kind_t clockid_t;\ntypedef long time_t;\n"
+        ));
+        assert!(out.contains("int clock_gettime(clockid_t clock, struct timespec *tp);\n"));
+    }
+
+    #[test]
+    fn empty_structs_and_enums_are_not_defined() {
+        let document = time();
+        let out = render_header(&document, &document.headers[1]);
+
+        assert!(out.contains("struct opaque;\nstruct timespec;\n"));
+        assert!(!out.contains("struct opaque {"));
+        assert!(out.contains("struct timespec {\n    time_t tv_sec;\n};"));
+        assert!(out.contains("enum clock_kind {\n    CLOCK_A,\n};"));
+        assert!(!out.contains("empty"));
+    }
+
+    #[test]
+    fn headers_include_the_headers_defining_their_types() {
+        let document = time();
+        let out = render_header(&document, &document.headers[0]);
+
+        assert!(
+            out.contains("#ifndef CAPIBARA_APP_H\n#define CAPIBARA_APP_H\n\n#include \"time.h\"\n")
+        );
+        assert!(out.ends_with("time_t now(void);\n\n#endif /* CAPIBARA_APP_H */\n"));
+    }
+
+    #[test]
+    fn export_writes_headers_and_the_check_file() {
+        let root = temp_dir("c-header");
+        export(&time(), root.to_str().unwrap()).unwrap();
+
+        assert!(root.join("app.h").is_file());
+        assert_eq!(
+            fs::read_to_string(root.join(CHECK_FILE)).unwrap(),
+            "/* Generated by capibara-processor. */\n\n#include \"app.h\"\n#include \"time.h\"\n"
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn deprecated_declarations_get_a_one_line_comment() {
+        let deprecated = Deprecated {
+            since: None,
+            removed: Some(String::from("POSIX.1-2008")),
+            reason: String::from("Use a */ safer\nclock."),
+            replacement: Some(String::from("time/clock_gettime")),
+        };
+
+        assert_eq!(
+            deprecation_comment(&Some(deprecated)),
+            "/* Deprecated, removed in POSIX.1-2008. Use a * / safer clock. Use clock_gettime instead. */\n"
+        );
+        assert_eq!(deprecation_comment(&None), "");
+    }
+}
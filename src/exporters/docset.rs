@@ -0,0 +1,133 @@
+use super::{entities, html};
+use crate::Document;
+use rusqlite::{params, Connection};
+use std::{error::Error, fs, path::Path};
+
+/// Builds a Dash/Zeal `.docset` bundle at `output_path`: the HTML site under
+/// `Contents/Resources/Documents` plus the `docSet.dsidx` search index.
+pub(crate) fn export(document: &Document, output_path: &str) -> Result<(), Box<dyn Error>> {
+    let root = Path::new(output_path);
+    let contents = root.join("Contents");
+    let resources = contents.join("Resources");
+    let documents = resources.join("Documents");
+
+    fs::create_dir_all(&documents)?;
+    html::export(document, documents.to_str().unwrap())?;
+
+    let bundle_name = root
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("Capibara");
+
+    fs::write(contents.join("Info.plist"), info_plist(bundle_name))?;
+
+    let index_path = resources.join("docSet.dsidx");
+    let _ = fs::remove_file(&index_path);
+
+    let mut connection = Connection::open(index_path)?;
+    let transaction = connection.transaction()?;
+    transaction.execute_batch(
+        "CREATE TABLE searchIndex(id INTEGER PRIMARY KEY, name TEXT, type TEXT, path TEXT);
+         CREATE UNIQUE INDEX anchor ON searchIndex (name, type, path);",
+    )?;
+
+    for header in document.headers.iter() {
+        transaction.execute(
+            "INSERT OR IGNORE INTO searchIndex(name, type, path) VALUES (?1, ?2, ?3)",
+            params![&*header.name, "File", format!("{}/index.html", header._ref)],
+        )?;
+    }
+
+    // The exporter's entity kinds already line up with Dash's entry types.
+    for entity in entities(document) {
+        transaction.execute(
+            "INSERT OR IGNORE INTO searchIndex(name, type, path) VALUES (?1, ?2, ?3)",
+            params![
+                entity.name,
+                entity.kind,
+                format!("{}/{}.html", entity.header._ref, entity.file_stem())
+            ],
+        )?;
+    }
+
+    transaction.commit()?;
+
+    Ok(())
+}
+
+fn info_plist(bundle_name: &str) -> String {
+    let identifier = bundle_name.to_lowercase().replace(' ', "-");
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleIdentifier</key>
+    <string>{}</string>
+    <key>CFBundleName</key>
+    <string>{}</string>
+    <key>DocSetPlatformFamily</key>
+    <string>{}</string>
+    <key>isDashDocset</key>
+    <true/>
+    <key>dashIndexFilePath</key>
+    <string>index.html</string>
+</dict>
+</plist>
+"#,
+        html::escape(&identifier),
+        html::escape(bundle_name),
+        html::escape(&identifier)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{document, function, header, object_macro, temp_dir};
+
+    #[test]
+    fn bundle_has_pages_plist_and_search_index() {
+        let document = document(
+            vec![header("stdio", &["linux"])],
+            vec![function("stdio", "puts", &["linux"])],
+            vec![object_macro("stdio", "EOF", &["linux"])],
+            vec![],
+            vec![],
+        );
+        let root = temp_dir("docset").join("Capibara C.docset");
+        export(&document, root.to_str().unwrap()).unwrap();
+
+        let documents = root.join("Contents/Resources/Documents");
+        assert!(documents.join("stdio/fn-puts.html").is_file());
+
+        let plist = fs::read_to_string(root.join("Contents/Info.plist")).unwrap();
+        assert!(plist.contains("<string>capibara-c</string>"));
+        assert!(plist.contains("<string>Capibara C</string>"));
+
+        let connection = Connection::open(root.join("Contents/Resources/docSet.dsidx")).unwrap();
+        let mut statement = connection
+            .prepare("SELECT name, type, path FROM searchIndex ORDER BY id")
+            .unwrap();
+        let entries: Vec<(String, String, String)> = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        fs::remove_dir_all(root.parent().unwrap()).unwrap();
+
+        let entries: Vec<(&str, &str, &str)> = entries
+            .iter()
+            .map(|(name, kind, path)| (name.as_str(), kind.as_str(), path.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("stdio.h", "File", "stdio/index.html"),
+                ("puts", "Function", "stdio/fn-puts.html"),
+                ("EOF", "Macro", "stdio/mo-EOF.html")
+            ]
+        );
+    }
+}
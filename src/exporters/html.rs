@@ -0,0 +1,902 @@
+use super::{
+    attribute_terms, deprecation_summary, entities, enum_definition, feature_test_define,
+    feature_test_requirement, feature_test_synopsis, find_entity, function_signature, link_notes,
+    macro_signature, return_convention_notes, root_prefix, struct_definition, typedef_definition,
+    typedef_target, EntityRef,
+};
+use crate::{
+    definitions::{
+        _macro::{Macro, MacroKind},
+        _struct::Struct,
+        attributes::Attributes,
+        deprecated::Deprecated,
+        enumeration::Enumeration,
+        error_condition::ErrorCondition,
+        feature_test_macro::FeatureTestMacro,
+        function::{Function, Return},
+        header::{Header, HeaderSummary},
+        link::Link,
+        typedef::Typedef,
+    },
+    Document,
+};
+use std::{fs, io, path::Path};
+
+const STYLESHEET: &str = r#"body { font-family: sans-serif; max-width: 960px; margin: 0 auto; padding: 1em; color: #222; }
+a { color: #0b5394; text-decoration: none; }
+a:hover { text-decoration: underline; }
+nav { margin-bottom: 1em; font-size: 0.9em; }
+table { border-collapse: collapse; width: 100%; margin: 1em 0; }
+th, td { border: 1px solid #ddd; padding: 0.4em 0.6em; text-align: left; vertical-align: top; }
+th { background: #f4f4f4; }
+pre { background: #f8f8f8; border: 1px solid #e4e4e4; padding: 0.8em; overflow-x: auto; }
+.badge { display: inline-block; padding: 0.1em 0.5em; margin-right: 0.3em; border-radius: 0.6em; background: #e0e0e0; font-size: 0.8em; }
+.os-linux { background: #fff3c4; }
+.os-windows { background: #cfe8ff; }
+.os-macos { background: #e6e6e6; }
+.deprecated { border-left: 4px solid #c0392b; background: #fdecea; padding: 0.1em 0.8em; margin: 1em 0; }
+.badge.deprecated { background: #f7c6c0; margin-left: 0.5em; }
+.kind { color: #777; font-size: 0.8em; margin-left: 0.5em; }
+.kw { color: #a626a4; }
+.ty { color: #c18401; }
+.str { color: #50a14f; }
+.num { color: #986801; }
+.com { color: #a0a1a7; font-style: italic; }
+.pp { color: #4078f2; }
+footer { margin-top: 3em; font-size: 0.8em; color: #777; }
+"#;
+
+const C_KEYWORDS: &[&str] = &[
+    "break", "case", "const", "continue", "default", "do", "else", "enum", "extern", "for", "goto",
+    "if", "inline", "register", "restrict", "return", "sizeof", "static", "struct", "switch",
+    "typedef", "union", "volatile", "while",
+];
+
+const C_TYPES: &[&str] = &[
+    "_Bool", "bool", "char", "double", "float", "int", "long", "short", "signed", "unsigned",
+    "void", "size_t", "ssize_t", "FILE",
+];
+
+/// Writes a static HTML site for the document to `output_path`: an index of
+/// headers, one page per header and one page per entity.
+pub(crate) fn export(document: &Document, output_path: &str) -> io::Result<()> {
+    let root = Path::new(output_path);
+    fs::create_dir_all(root)?;
+    fs::write(root.join("style.css"), STYLESHEET)?;
+    fs::write(root.join("index.html"), render_index(document))?;
+
+    for header in document.headers.iter() {
+        let header_dir = root.join(&*header._ref);
+        fs::create_dir_all(&header_dir)?;
+        fs::write(
+            header_dir.join("index.html"),
+            render_header(document, header),
+        )?;
+    }
+
+    for function in document.functions.iter() {
+        write_entity_page(root, &function.header, "fn-", &function.name, || {
+            render_function(document, function)
+        })?;
+    }
+
+    for _macro in document.macros.iter() {
+        write_entity_page(root, &_macro.header, "mo-", &_macro.name, || {
+            render_macro(document, _macro)
+        })?;
+    }
+
+    for _enum in document.enums.iter() {
+        write_entity_page(root, &_enum.header, "em-", &_enum.name, || {
+            render_enum(document, _enum)
+        })?;
+    }
+
+    for _struct in document.structs.iter() {
+        write_entity_page(root, &_struct.header, "st-", &_struct.name, || {
+            render_struct(document, _struct)
+        })?;
+    }
+
+    for typedef in document.typedefs.iter() {
+        write_entity_page(root, &typedef.header, "tf-", &typedef.name, || {
+            render_typedef(document, typedef)
+        })?;
+    }
+
+    Ok(())
+}
+
+fn write_entity_page<F: FnOnce() -> String>(
+    root: &Path,
+    header: &HeaderSummary,
+    prefix: &str,
+    name: &str,
+    render: F,
+) -> io::Result<()> {
+    let header_dir = root.join(&*header._ref);
+    fs::create_dir_all(&header_dir)?;
+    fs::write(
+        header_dir.join(format!("{}{}.html", prefix, name)),
+        render(),
+    )
+}
+
+fn entity_href(root: &str, entity: &EntityRef) -> String {
+    format!(
+        "{}{}/{}.html",
+        root,
+        escape(&entity.header._ref),
+        escape(&entity.file_stem())
+    )
+}
+
+fn page(root: &str, title: &str, document: &Document, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{} - Capibara</title>\n<link rel=\"stylesheet\" href=\"{}style.css\">\n</head>\n<body>\n<nav><a href=\"{}index.html\">Capibara</a></nav>\n{}\n<footer>Generated {} from <a href=\"{}\">{}</a></footer>\n</body>\n</html>\n",
+        escape(title),
+        root,
+        root,
+        body,
+        escape(&document.build_date),
+        escape(&document.reference_url),
+        escape(&document.reference_url)
+    )
+}
+
+fn render_index(document: &Document) -> String {
+    let mut body = String::from(
+        "<h1>Headers</h1>\n<table>\n<tr><th>Header</th><th>Summary</th><th>OS</th></tr>\n",
+    );
+
+    for header in document.headers.iter() {
+        body += &format!(
+            "<tr><td><a href=\"{}/index.html\">{}</a></td><td>{}</td><td>{}</td></tr>\n",
+            escape(&header._ref),
+            escape(&header.name),
+            escape(&header.summary),
+            os_badges(&header.os_affinity)
+        );
+    }
+
+    body += "</table>\n";
+
+    page("", "Headers", document, &body)
+}
+
+fn render_header(document: &Document, header: &Header) -> String {
+    let root = root_prefix(&header._ref);
+    let mut body = format!(
+        "<h1>{}</h1>\n<p>{}</p>\n<p>{}</p>\n",
+        escape(&header.name),
+        escape(&header.summary),
+        os_badges(&header.os_affinity)
+    );
+
+    let members: Vec<EntityRef> = entities(document)
+        .into_iter()
+        .filter(|entity| entity.header._ref == header._ref)
+        .collect();
+
+    for (kind, title) in [
+        ("Function", "Functions"),
+        ("Macro", "Macros"),
+        ("Enum", "Enums"),
+        ("Struct", "Structs"),
+        ("Type", "Types"),
+    ] {
+        let section: Vec<&EntityRef> = members.iter().filter(|m| m.kind == kind).collect();
+
+        if section.is_empty() {
+            continue;
+        }
+
+        body += &format!(
+            "<h2>{}</h2>\n<table>\n<tr><th>Name</th><th>Summary</th></tr>\n",
+            title
+        );
+
+        for entity in section {
+            body += &format!(
+                "<tr><td><a href=\"{}\">{}</a>{}</td><td>{}</td></tr>\n",
+                entity_href(&root, entity),
+                escape(entity.name),
+                if entity.deprecated.is_some() {
+                    "<span class=\"badge deprecated\">deprecated</span>"
+                } else {
+                    ""
+                },
+                escape(entity.summary)
+            );
+        }
+
+        body += "</table>\n";
+    }
+
+    page(&root, &header.name, document, &body)
+}
+
+fn entity_heading(root: &str, kind: &str, name: &str, header: &HeaderSummary) -> String {
+    format!(
+        "<h1>{}<span class=\"kind\">{}</span></h1>\n<p>Defined in <a href=\"{}{}/index.html\"><code>{}</code></a></p>\n",
+        escape(name),
+        kind,
+        root,
+        escape(&header._ref),
+        escape(&header.name)
+    )
+}
+
+fn render_function(document: &Document, function: &Function) -> String {
+    let root = root_prefix(&function.header._ref);
+    let mut body = entity_heading(&root, "Function", &function.name, &function.header);
+    body += &render_deprecated(document, &root, &function.deprecated);
+
+    body += &format!("<p>{}</p>\n", escape(&function.summary));
+    body += &format!(
+        "<pre><code>{}</code></pre>\n",
+        highlight_c(&synopsis(
+            &function.header,
+            &function.feature_test_macros,
+            &function_signature(function)
+        ))
+    );
+    body += &render_link(&function.link);
+    body += &format!("<p>{}</p>\n", os_badges(&function.os_affinity));
+    body += &render_feature_test_macros(document, &root, &function.feature_test_macros);
+    body += &render_attributes(function.attributes.as_ref());
+
+    if !function.parameters.is_empty() {
+        body += "<h2>Parameters</h2>\n<table>\n<tr><th>Name</th><th>Type</th><th>Description</th></tr>\n";
+        for parameter in function.parameters.iter() {
+            body += &format!(
+                "<tr><td><code>{}</code></td><td><code>{}</code></td><td>{}</td></tr>\n",
+                escape(&parameter.name),
+                escape(&parameter._type),
+                escape(&parameter.description)
+            );
+        }
+        body += "</table>\n";
+    }
+
+    body += &format!(
+        "<h2>Return Value</h2>\n<p><code>{}</code></p>\n{}",
+        escape(&function.returns._type),
+        paragraphs(&function.returns.description)
+    );
+    body += &render_return_convention(&function.returns);
+    body += &render_errors(document, &root, &function.errors);
+    body += &format!(
+        "<h2>Description</h2>\n{}",
+        paragraphs(&function.description)
+    );
+
+    for example in function.examples.iter() {
+        body += &render_example(&example.title, &example.code);
+    }
+
+    body += &render_associated(document, &root, &function.associated);
+
+    page(&root, &function.name, document, &body)
+}
+
+fn render_macro(document: &Document, _macro: &Macro) -> String {
+    let root = root_prefix(&_macro.header._ref);
+    let mut body = entity_heading(&root, "Macro", &_macro.name, &_macro.header);
+    body += &render_deprecated(document, &root, &_macro.deprecated);
+
+    body += &format!("<p>{}</p>\n", escape(&_macro.summary));
+    body += &format!(
+        "<pre><code>{}</code></pre>\n",
+        highlight_c(&synopsis(
+            &_macro.header,
+            &_macro.feature_test_macros,
+            &macro_signature(_macro)
+        ))
+    );
+
+    match &_macro.kind {
+        MacroKind::Object(_) => {
+            body += &format!("<p>{}</p>\n", os_badges(&_macro.os_affinity));
+            body += &render_feature_test_macros(document, &root, &_macro.feature_test_macros);
+        }
+        MacroKind::Function(function) => {
+            body += &format!("<p>{}</p>\n", os_badges(&_macro.os_affinity));
+            body += &render_feature_test_macros(document, &root, &_macro.feature_test_macros);
+            body += &render_attributes(function.attributes.as_deref());
+
+            if !function.parameters.is_empty() {
+                body +=
+                    "<h2>Parameters</h2>\n<table>\n<tr><th>Name</th><th>Description</th></tr>\n";
+                for parameter in function.parameters.iter() {
+                    body += &format!(
+                        "<tr><td><code>{}</code></td><td>{}</td></tr>\n",
+                        escape(&parameter.name),
+                        escape(&parameter.description)
+                    );
+                }
+                body += "</table>\n";
+            }
+
+            body += &format!(
+                "<h2>Return Value</h2>\n<p><code>{}</code></p>\n{}",
+                escape(&function.returns._type),
+                paragraphs(&function.returns.description)
+            );
+            body += &render_return_convention(&function.returns);
+            body += &render_errors(document, &root, &function.errors);
+        }
+    }
+
+    body += &format!("<h2>Description</h2>\n{}", paragraphs(&_macro.description));
+
+    if let MacroKind::Function(function) = &_macro.kind {
+        for example in function.examples.iter() {
+            body += &render_example(&example.title, &example.code);
+        }
+    }
+
+    body += &render_associated(document, &root, &_macro.associated);
+
+    page(&root, &_macro.name, document, &body)
+}
+
+fn render_enum(document: &Document, _enum: &Enumeration) -> String {
+    let root = root_prefix(&_enum.header._ref);
+    let mut body = entity_heading(&root, "Enum", &_enum.name, &_enum.header);
+    body += &render_deprecated(document, &root, &_enum.deprecated);
+
+    body += &format!("<p>{}</p>\n", escape(&_enum.summary));
+    body += &format!(
+        "<pre><code>{}</code></pre>\n",
+        highlight_c(&enum_definition(_enum))
+    );
+    body += &format!("<p>{}</p>\n", os_badges(&_enum.os_affinity));
+
+    if !_enum.variants.is_empty() {
+        body += "<h2>Variants</h2>\n<table>\n<tr><th>Name</th><th>Description</th></tr>\n";
+        for variant in _enum.variants.iter() {
+            body += &format!(
+                "<tr><td><code>{}</code></td><td>{}</td></tr>\n",
+                escape(&variant.name),
+                escape(&variant.description)
+            );
+        }
+        body += "</table>\n";
+    }
+
+    body += &format!("<h2>Description</h2>\n{}", paragraphs(&_enum.description));
+
+    page(&root, &_enum.name, document, &body)
+}
+
+fn render_struct(document: &Document, _struct: &Struct) -> String {
+    let root = root_prefix(&_struct.header._ref);
+    let mut body = entity_heading(&root, "Struct", &_struct.name, &_struct.header);
+    body += &render_deprecated(document, &root, &_struct.deprecated);
+
+    body += &format!("<p>{}</p>\n", escape(&_struct.summary));
+    body += &format!(
+        "<pre><code>{}</code></pre>\n",
+        highlight_c(&feature_test_synopsis(
+            &_struct.header,
+            &_struct.feature_test_macros,
+            &struct_definition(_struct)
+        ))
+    );
+    body += &format!("<p>{}</p>\n", os_badges(&_struct.os_affinity));
+    body += &render_feature_test_macros(document, &root, &_struct.feature_test_macros);
+
+    if !_struct.fields.is_empty() {
+        body +=
+            "<h2>Fields</h2>\n<table>\n<tr><th>Name</th><th>Type</th><th>Description</th></tr>\n";
+        for field in _struct.fields.iter() {
+            body += &format!(
+                "<tr><td><code>{}</code></td><td><code>{}</code></td><td>{}</td></tr>\n",
+                escape(&field.name),
+                escape(&field._type),
+                escape(&field.description)
+            );
+        }
+        body += "</table>\n";
+    }
+
+    body += &format!("<h2>Description</h2>\n{}", paragraphs(&_struct.description));
+
+    page(&root, &_struct.name, document, &body)
+}
+
+fn render_typedef(document: &Document, typedef: &Typedef) -> String {
+    let root = root_prefix(&typedef.header._ref);
+    let mut body = entity_heading(&root, "Type", &typedef.name, &typedef.header);
+    body += &render_deprecated(document, &root, &typedef.deprecated);
+
+    body += &format!("<p>{}</p>\n", escape(&typedef.summary));
+    body += &format!(
+        "<pre><code>{}</code></pre>\n",
+        highlight_c(&feature_test_synopsis(
+            &typedef.header,
+            &typedef.feature_test_macros,
+            &typedef_definition(typedef)
+        ))
+    );
+    body += &format!("<p>{}</p>\n", os_badges(&typedef.os_affinity));
+    body += &render_feature_test_macros(document, &root, &typedef.feature_test_macros);
+
+    if let Some(target) = typedef_target(typedef) {
+        body += &format!(
+            "<h2>Aliases</h2>\n<p><a href=\"{}\"><code>{}</code></a> from <code>{}</code></p>\n",
+            entity_href(&root, &target),
+            escape(target.name),
+            escape(&target.header.name)
+        );
+    }
+
+    body += &format!("<h2>Description</h2>\n{}", paragraphs(&typedef.description));
+
+    page(&root, &typedef.name, document, &body)
+}
+
+/// A declaration preceded by the `#include` it needs.
+fn synopsis(
+    header: &HeaderSummary,
+    feature_test_macros: &[FeatureTestMacro],
+    declaration: &str,
+) -> String {
+    format!(
+        "{}#include <{}>\n\n{}",
+        feature_test_define(feature_test_macros),
+        header.name,
+        declaration
+    )
+}
+
+fn render_example(title: &str, code: &str) -> String {
+    format!(
+        "<h3>Example: {}</h3>\n<pre><code>{}</code></pre>\n",
+        escape(title),
+        highlight_c(code)
+    )
+}
+
+fn render_deprecated(document: &Document, root: &str, deprecated: &Option<Deprecated>) -> String {
+    let deprecated = match deprecated {
+        Some(deprecated) => deprecated,
+        None => return String::new(),
+    };
+
+    let mut out = format!(
+        "<div class=\"deprecated\">\n<p>{}</p>\n",
+        escape(&deprecation_summary(deprecated))
+    );
+
+    if let Some(replacement) = &deprecated.replacement {
+        match find_entity(document, replacement) {
+            Some(entity) => {
+                out += &format!(
+                    "<p>Use <a href=\"{}\"><code>{}</code></a> instead.</p>\n",
+                    entity_href(root, &entity),
+                    escape(entity.name)
+                )
+            }
+            None => out += &format!("<p>Use <code>{}</code> instead.</p>\n", escape(replacement)),
+        }
+    }
+
+    out + "</div>\n"
+}
+
+fn render_link(link: &Option<Link>) -> String {
+    match link {
+        Some(link) => link_notes(link, |arguments| {
+            format!("<code>{}</code>", escape(arguments))
+        })
+        .iter()
+        .map(|note| format!("<p>{}</p>\n", note))
+        .collect(),
+        None => String::new(),
+    }
+}
+
+fn render_return_convention(returns: &Return) -> String {
+    match &returns.convention {
+        Some(convention) => return_convention_notes(convention, |value| {
+            format!("<code>{}</code>", escape(value))
+        })
+        .iter()
+        .map(|note| format!("<p>{}</p>\n", note))
+        .collect(),
+        None => String::new(),
+    }
+}
+
+/// The errno values a function fails with, linked to their macro pages.
+fn render_errors(document: &Document, root: &str, errors: &[ErrorCondition]) -> String {
+    if errors.is_empty() {
+        return String::new();
+    }
+
+    let mut out =
+        String::from("<h2>Errors</h2>\n<table>\n<tr><th>Error</th><th>Condition</th></tr>\n");
+
+    for error in errors.iter() {
+        let errno = match error._ref.as_ref().and_then(|r| find_entity(document, r)) {
+            Some(entity) => format!(
+                "<a href=\"{}\"><code>{}</code></a>",
+                entity_href(root, &entity),
+                escape(&error.errno)
+            ),
+            None => format!("<code>{}</code>", escape(&error.errno)),
+        };
+
+        out += &format!(
+            "<tr><td>{}</td><td>{}</td></tr>\n",
+            errno,
+            escape(&error.condition)
+        );
+    }
+
+    out + "</table>\n"
+}
+
+/// The feature test macros an entity needs, linked to their pages when the
+/// corpus documents them.
+fn render_feature_test_macros(
+    document: &Document,
+    root: &str,
+    feature_test_macros: &[FeatureTestMacro],
+) -> String {
+    if feature_test_macros.is_empty() {
+        return String::new();
+    }
+
+    let requirements: Vec<String> = feature_test_macros
+        .iter()
+        .map(|feature_test_macro| {
+            let requirement = escape(&feature_test_requirement(feature_test_macro));
+
+            match feature_test_macro
+                ._ref
+                .as_deref()
+                .and_then(|reference| find_entity(document, reference))
+            {
+                Some(entity) => format!(
+                    "<a href=\"{}\"><code>{}</code></a>",
+                    entity_href(root, &entity),
+                    requirement
+                ),
+                None => format!("<code>{}</code>", requirement),
+            }
+        })
+        .collect();
+
+    format!(
+        "<p>Requires feature test macro {}</p>\n",
+        requirements.join(" or ")
+    )
+}
+
+fn render_attributes(attributes: Option<&Attributes>) -> String {
+    let terms = match attributes {
+        Some(attributes) => {
+            attribute_terms(attributes, |term| format!("<code>{}</code>", escape(term)))
+        }
+        None => return String::new(),
+    };
+
+    if terms.is_empty() {
+        String::new()
+    } else {
+        format!("<p>Attributes: {}</p>\n", terms.join(", "))
+    }
+}
+
+fn render_associated(document: &Document, root: &str, associated: &[String]) -> String {
+    if associated.is_empty() {
+        return String::new();
+    }
+
+    let mut out = String::from("<h2>See Also</h2>\n<ul>\n");
+
+    for reference in associated.iter() {
+        match find_entity(document, reference) {
+            Some(entity) => {
+                out += &format!(
+                    "<li><a href=\"{}\"><code>{}</code></a> - {}</li>\n",
+                    entity_href(root, &entity),
+                    escape(entity.name),
+                    escape(entity.summary)
+                )
+            }
+            None => out += &format!("<li><code>{}</code></li>\n", escape(reference)),
+        }
+    }
+
+    out + "</ul>\n"
+}
+
+fn os_badges(os_affinity: &[String]) -> String {
+    os_affinity
+        .iter()
+        .map(|os| {
+            format!(
+                "<span class=\"badge os-{}\">{}</span>",
+                escape(&os.to_lowercase()),
+                escape(os)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("")
+}
+
+fn paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| format!("<p>{}</p>\n", escape(p)))
+        .collect()
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// A small C tokenizer that wraps comments, strings, numbers, keywords and
+/// preprocessor lines in `<span>`s for the stylesheet to colour.
+fn highlight_c(code: &str) -> String {
+    let chars: Vec<char> = code.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    let mut line_start = true;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        let class = if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            Some("com")
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i = (i + 2).min(chars.len());
+            Some("com")
+        } else if c == '"' || c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != c && chars[i] != '\n' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            Some("str")
+        } else if c == '#' && line_start {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            Some("pp")
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            Some("num")
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            if C_KEYWORDS.contains(&word.as_str()) {
+                Some("kw")
+            } else if C_TYPES.contains(&word.as_str()) || word.ends_with("_t") {
+                Some("ty")
+            } else {
+                None
+            }
+        } else {
+            i += 1;
+            None
+        };
+
+        let token: String = chars[start..i].iter().collect();
+
+        match class {
+            Some(class) => out += &format!("<span class=\"{}\">{}</span>", class, escape(&token)),
+            None => out += &escape(&token),
+        }
+
+        if c == '\n' {
+            line_start = true;
+        } else if !c.is_whitespace() {
+            line_start = false;
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{document, function, header, object_macro, temp_dir};
+    use serde_json::json;
+
+    fn stdio() -> Document {
+        let mut puts = function("stdio", "puts", &["linux", "windows"]);
+        puts["parameters"] =
+            json!([{ "name": "s", "type": "const char *", "description": "The <line>." }]);
+        puts["associated"] = json!(["stdio/EOF", "stdio/missing"]);
+
+        document(
+            vec![header("stdio", &["linux", "windows"])],
+            vec![puts],
+            vec![object_macro("stdio", "EOF", &["linux"])],
+            vec![],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn index_escapes_header_references() {
+        let document = document(
+            vec![header("a\"b", &["linux"])],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        );
+
+        let index = render_index(&document);
+
+        assert!(index.contains("<a href=\"a&quot;b/index.html\">a&quot;b.h</a>"));
+        assert!(index.contains("<span class=\"badge os-linux\">linux</span>"));
+    }
+
+    #[test]
+    fn function_pages_show_the_include_signature_and_links() {
+        let document = stdio();
+        let page = render_function(&document, &document.functions[0]);
+
+        assert!(page.contains("<span class=\"pp\">#include &lt;stdio.h&gt;</span>"));
+        assert!(page.contains("<span class=\"kw\">const</span> <span class=\"ty\">char</span> *s"));
+        assert!(page.contains("<td>The &lt;line&gt;.</td>"));
+        assert!(page.contains(
+            "<li><a href=\"../stdio/mo-EOF.html\"><code>EOF</code></a> - The EOF summary.</li>"
+        ));
+        assert!(page.contains("<li><code>stdio/missing</code></li>"));
+    }
+
+    #[test]
+    fn macro_pages_show_the_include() {
+        let document = stdio();
+        let page = render_macro(&document, &document.macros[0]);
+
+        assert!(page.contains("<span class=\"pp\">#include &lt;stdio.h&gt;</span>"));
+        assert!(page.contains("<span class=\"pp\">#define EOF</span>"));
+    }
+
+    #[test]
+    fn export_writes_a_page_per_header_and_entity() {
+        let root = temp_dir("html");
+        export(&stdio(), root.to_str().unwrap()).unwrap();
+
+        for page in [
+            "style.css",
+            "index.html",
+            "stdio/index.html",
+            "stdio/fn-puts.html",
+            "stdio/mo-EOF.html",
+        ] {
+            assert!(root.join(page).is_file(), "{}", page);
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn deprecated_entities_are_flagged_and_link_their_replacement() {
+        let mut document = stdio();
+        document.macros[0].deprecated = Some(Deprecated {
+            since: Some(String::from("C23")),
+            removed: None,
+            reason: String::from("Use <puts>."),
+            replacement: Some(String::from("stdio/puts")),
+        });
+
+        let page = render_macro(&document, &document.macros[0]);
+        assert!(page.contains("<p>Deprecated since C23. Use &lt;puts&gt;.</p>"));
+        assert!(page.contains("<a href=\"../stdio/fn-puts.html\"><code>puts</code></a> instead."));
+
+        let index = render_header(&document, &document.headers[0]);
+        assert!(index.contains("EOF</a><span class=\"badge deprecated\">deprecated</span>"));
+    }
+    #[test]
+    fn feature_test_macros_are_defined_and_linked_to_their_macro() {
+        let mut document = stdio();
+        document.functions[0].feature_test_macros = vec![
+            FeatureTestMacro {
+                name: String::from("EOF"),
+                value: None,
+                _ref: Some(String::from("stdio/EOF")),
+            },
+            FeatureTestMacro {
+                name: String::from("_POSIX_C_SOURCE"),
+                value: Some(String::from("200809L")),
+                _ref: None,
+            },
+        ];
+
+        let page = render_function(&document, &document.functions[0]);
+        assert!(page.contains("#define EOF /* or _POSIX_C_SOURCE &gt;= 200809L */"));
+        assert!(page.contains("<span class=\"pp\">#include &lt;stdio.h&gt;</span>"));
+        assert!(page.contains(
+            "Requires feature test macro <a href=\"../stdio/mo-EOF.html\"><code>EOF</code></a> or <code>_POSIX_C_SOURCE &gt;= 200809L</code>"
+        ));
+    }
+
+    #[test]
+    fn function_pages_list_their_attributes() {
+        let mut document = stdio();
+        document.functions[0].attributes = Some(
+            serde_json::from_value(json!({ "async_signal_safety": { "level": "AS-Safe" } }))
+                .unwrap(),
+        );
+
+        let page = render_function(&document, &document.functions[0]);
+        assert!(page.contains("<p>Attributes: <code>AS-Safe</code></p>\n"));
+    }
+
+    #[test]
+    fn function_pages_say_what_to_link() {
+        let mut document = stdio();
+        document.functions[0].link = Some(
+            serde_json::from_value(json!({
+                "flags": ["-pthread"],
+                "os_overrides": [{ "os": ["windows"], "libraries": ["ws2_32"] }],
+            }))
+            .unwrap(),
+        );
+
+        let page = render_function(&document, &document.functions[0]);
+        assert!(page.contains(
+            "<p>Link with <code>-pthread</code>.</p>\n<p>On windows, link with <code>-lws2_32</code>.</p>\n"
+        ));
+    }
+
+    #[test]
+    fn function_pages_state_the_return_convention_and_link_errors() {
+        let mut document = stdio();
+        document.functions[0].returns.convention =
+            Some(serde_json::from_value(json!({ "failure": "EOF", "sets_errno": true })).unwrap());
+        document.functions[0].errors = serde_json::from_value(json!([
+            { "errno": "EOF", "condition": "The <stream> ended.", "ref": "stdio/EOF" },
+            { "errno": "EIO", "condition": "A write failed." },
+        ]))
+        .unwrap();
+
+        let page = render_function(&document, &document.functions[0]);
+        assert!(page.contains(
+            "<p>On failure, returns <code>EOF</code> and sets <code>errno</code> to indicate the error.</p>\n"
+        ));
+        assert!(page.contains(
+            "<tr><td><a href=\"../stdio/mo-EOF.html\"><code>EOF</code></a></td><td>The &lt;stream&gt; ended.</td></tr>\n"
+        ));
+        assert!(page.contains("<tr><td><code>EIO</code></td><td>A write failed.</td></tr>\n"));
+    }
+}
@@ -0,0 +1,577 @@
+use super::{
+    attribute_terms, deprecation_summary, entities, feature_test_define, find_entity,
+    function_signature, link_notes, macro_signature, return_convention_notes, EntityRef,
+};
+use crate::{
+    definitions::{
+        _macro::{Macro, MacroKind},
+        attributes::Attributes,
+        deprecated::Deprecated,
+        error_condition::ErrorCondition,
+        feature_test_macro::FeatureTestMacro,
+        function::{Function, Return},
+        header::{Header, HeaderSummary},
+        link::Link,
+    },
+    Document,
+};
+use std::{fs, io, path::Path};
+
+/// Writes section 3 pages for every function and function-like macro and a
+/// section 7 page per header into `man3/` and `man7/` under `output_path`.
+pub(crate) fn export(document: &Document, output_path: &str) -> io::Result<()> {
+    let root = Path::new(output_path);
+    let man3 = root.join("man3");
+    let man7 = root.join("man7");
+    fs::create_dir_all(&man3)?;
+    fs::create_dir_all(&man7)?;
+
+    for function in document.functions.iter() {
+        fs::write(
+            man3.join(format!("{}.3", page_name(&function.name))),
+            render_function(document, function),
+        )?;
+    }
+
+    for _macro in document.macros.iter() {
+        if let MacroKind::Function(_) = _macro.kind {
+            fs::write(
+                man3.join(format!("{}.3", page_name(&_macro.name))),
+                render_macro(document, _macro),
+            )?;
+        }
+    }
+
+    for header in document.headers.iter() {
+        fs::write(
+            man7.join(format!("{}.7", header_page_name(&header.name))),
+            render_header(document, header),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn page_name(name: &str) -> String {
+    format!("capibara-{}", name)
+}
+
+fn header_page_name(header_name: &str) -> String {
+    page_name(&header_name.replace('/', "_"))
+}
+
+/// Section and page an entity is documented under: functions and
+/// function-like macros get their own section 3 page, everything else is
+/// covered by its header's section 7 page.
+fn man_reference(document: &Document, entity: &EntityRef) -> (String, u8) {
+    let own_page = match entity.kind {
+        "Function" => true,
+        "Macro" => document.macros.iter().any(|m| {
+            m.name.as_str() == entity.name
+                && m.header._ref == entity.header._ref
+                && matches!(m.kind, MacroKind::Function(_))
+        }),
+        _ => false,
+    };
+
+    if own_page {
+        (page_name(entity.name), 3)
+    } else {
+        (header_page_name(&entity.header.name), 7)
+    }
+}
+
+fn title(document: &Document, name: &str, section: u8) -> String {
+    format!(
+        ".TH \"{}\" {} \"{}\" \"Capibara\" \"Capibara Reference\"\n",
+        name.to_uppercase(),
+        section,
+        document
+            .build_date
+            .get(..10)
+            .unwrap_or(&document.build_date)
+    )
+}
+
+fn name_section(name: &str, summary: &str) -> String {
+    format!(
+        ".SH NAME\n{} \\- {}\n",
+        escape(name),
+        escape(inline(summary).as_str())
+    )
+}
+
+fn synopsis(
+    header: &HeaderSummary,
+    feature_test_macros: &[FeatureTestMacro],
+    declaration: &str,
+    link: Option<&Link>,
+) -> String {
+    let define = feature_test_define(feature_test_macros);
+    let mut out = format!(
+        ".SH SYNOPSIS\n.nf\n{}.B #include <{}>\n.PP\n{}\n.fi\n",
+        if define.is_empty() {
+            String::new()
+        } else {
+            format!(".B {}\n", escape(define.trim_end()))
+        },
+        escape(&header.name),
+        escape(declaration)
+    );
+
+    if let Some(link) = link {
+        let notes = link_notes(link, |arguments| {
+            format!("\\fI{}\\fP", arguments.replace('\\', "\\e"))
+        });
+
+        if !notes.is_empty() {
+            out += &format!(".PP\n{}\n", notes.join("\n.br\n"));
+        }
+    }
+
+    out
+}
+
+fn attributes(attributes: Option<&Attributes>) -> String {
+    let terms = match attributes {
+        Some(attributes) => attribute_terms(attributes, |term| format!("\\fI{}\\fP", escape(term))),
+        None => return String::new(),
+    };
+
+    if terms.is_empty() {
+        String::new()
+    } else {
+        format!(".SH ATTRIBUTES\n{}\n", terms.join(", "))
+    }
+}
+
+fn availability(os_affinity: &[String]) -> String {
+    format!(".SH AVAILABILITY\n{}\n", escape(&os_affinity.join(", ")))
+}
+
+fn deprecation(document: &Document, deprecated: &Option<Deprecated>) -> String {
+    let deprecated = match deprecated {
+        Some(deprecated) => deprecated,
+        None => return String::new(),
+    };
+
+    let mut out = format!(
+        ".SH DEPRECATED\n{}\n",
+        escape(&deprecation_summary(deprecated))
+    );
+
+    if let Some(replacement) = &deprecated.replacement {
+        match find_entity(document, replacement) {
+            Some(entity) => {
+                let (name, section) = man_reference(document, &entity);
+                out += &format!("Use\n.BR {} ({})\ninstead.\n", escape(&name), section);
+            }
+            None => out += &format!("Use\n.B {}\ninstead.\n", escape(replacement)),
+        }
+    }
+
+    out
+}
+
+fn return_convention(returns: &Return) -> String {
+    let notes = match &returns.convention {
+        Some(convention) => {
+            return_convention_notes(convention, |value| format!("\\fI{}\\fP", escape(value)))
+        }
+        None => return String::new(),
+    };
+
+    if notes.is_empty() {
+        String::new()
+    } else {
+        format!(".PP\n{}\n", notes.join("\n"))
+    }
+}
+
+fn errors(errors: &[ErrorCondition]) -> String {
+    let mut out = String::new();
+
+    for error in errors.iter() {
+        if out.is_empty() {
+            out += ".SH ERRORS\n";
+        }
+
+        out += &format!(
+            ".TP\n.B {}\n{}\n",
+            escape(&error.errno),
+            escape(inline(&error.condition).as_str())
+        );
+    }
+
+    out
+}
+
+fn examples<'a, I>(examples: I) -> String
+where
+    I: Iterator<Item = (&'a str, &'a str)>,
+{
+    let mut out = String::new();
+
+    for (title, code) in examples {
+        if out.is_empty() {
+            out += ".SH EXAMPLES\n";
+        }
+
+        out += &format!(
+            ".SS {}\n.PP\n.in +4n\n.EX\n{}\n.EE\n.in\n",
+            escape(title),
+            escape(code.trim_end())
+        );
+    }
+
+    out
+}
+
+fn see_also(document: &Document, associated: &[String]) -> String {
+    let mut references: Vec<(String, u8)> = Vec::new();
+
+    for reference in associated.iter() {
+        if let Some(entity) = find_entity(document, reference) {
+            let man_ref = man_reference(document, &entity);
+            if !references.contains(&man_ref) {
+                references.push(man_ref);
+            }
+        }
+    }
+
+    if references.is_empty() {
+        return String::new();
+    }
+
+    let lines: Vec<String> = references
+        .iter()
+        .map(|(name, section)| format!(".BR {} ({})", escape(name), section))
+        .collect();
+
+    format!(".SH SEE ALSO\n{}\n", lines.join(" ,\n"))
+}
+
+fn render_function(document: &Document, function: &Function) -> String {
+    let mut out = title(document, &page_name(&function.name), 3);
+
+    out += &name_section(&function.name, &function.summary);
+    out += &synopsis(
+        &function.header,
+        &function.feature_test_macros,
+        &function_signature(function),
+        function.link.as_ref(),
+    );
+    out += &deprecation(document, &function.deprecated);
+    out += &format!(".SH DESCRIPTION\n{}\n", paragraphs(&function.description));
+
+    for parameter in function.parameters.iter() {
+        out += &format!(
+            ".TP\n.I {}\n{}\n",
+            escape(&parameter.name),
+            escape(inline(&parameter.description).as_str())
+        );
+    }
+
+    out += &format!(
+        ".SH RETURN VALUE\n{}\n",
+        paragraphs(&function.returns.description)
+    );
+    out += &return_convention(&function.returns);
+    out += &errors(&function.errors);
+    out += &attributes(function.attributes.as_ref());
+    out += &availability(&function.os_affinity);
+    out += &examples(
+        function
+            .examples
+            .iter()
+            .map(|e| (e.title.as_str(), e.code.as_str())),
+    );
+    out += &see_also(document, &function.associated);
+
+    out
+}
+
+fn render_macro(document: &Document, _macro: &Macro) -> String {
+    let mut out = title(document, &page_name(&_macro.name), 3);
+    let mut example_section = String::new();
+
+    out += &name_section(&_macro.name, &_macro.summary);
+    out += &synopsis(
+        &_macro.header,
+        &_macro.feature_test_macros,
+        &macro_signature(_macro),
+        None,
+    );
+    out += &deprecation(document, &_macro.deprecated);
+    out += &format!(".SH DESCRIPTION\n{}\n", paragraphs(&_macro.description));
+
+    if let MacroKind::Function(function) = &_macro.kind {
+        for parameter in function.parameters.iter() {
+            out += &format!(
+                ".TP\n.I {}\n{}\n",
+                escape(&parameter.name),
+                escape(inline(&parameter.description).as_str())
+            );
+        }
+
+        out += &format!(
+            ".SH RETURN VALUE\n{}\n",
+            paragraphs(&function.returns.description)
+        );
+        out += &return_convention(&function.returns);
+        out += &errors(&function.errors);
+        out += &attributes(function.attributes.as_deref());
+        example_section = examples(
+            function
+                .examples
+                .iter()
+                .map(|e| (e.title.as_str(), e.code.as_str())),
+        );
+    }
+
+    out += &availability(&_macro.os_affinity);
+    out += &example_section;
+    out += &see_also(document, &_macro.associated);
+
+    out
+}
+
+fn render_header(document: &Document, header: &Header) -> String {
+    let page = header_page_name(&header.name);
+    let mut out = title(document, &page, 7);
+
+    out += &name_section(&page, &header.summary);
+    out += &format!(
+        ".SH SYNOPSIS\n.nf\n.B #include <{}>\n.fi\n",
+        escape(&header.name)
+    );
+    out += &format!(".SH DESCRIPTION\n{}\n", paragraphs(&header.summary));
+
+    let members: Vec<EntityRef> = entities(document)
+        .into_iter()
+        .filter(|entity| entity.header._ref == header._ref)
+        .collect();
+
+    for (kind, title) in [
+        ("Function", "Functions"),
+        ("Macro", "Macros"),
+        ("Enum", "Enums"),
+        ("Struct", "Structs"),
+        ("Type", "Types"),
+    ] {
+        let section: Vec<&EntityRef> = members.iter().filter(|m| m.kind == kind).collect();
+
+        if section.is_empty() {
+            continue;
+        }
+
+        out += &format!(".SS {}\n", title);
+
+        for entity in section {
+            out += &format!(
+                ".TP\n.B {}\n{}{}\n",
+                escape(entity.name),
+                if entity.deprecated.is_some() {
+                    "(Deprecated) "
+                } else {
+                    ""
+                },
+                escape(inline(entity.summary).as_str())
+            );
+        }
+    }
+
+    out += &availability(&header.os_affinity);
+
+    let pages: Vec<String> = members
+        .iter()
+        .filter_map(|m| match man_reference(document, m) {
+            (name, 3) => Some(format!(".BR {} (3)", escape(&name))),
+            _ => None,
+        })
+        .collect();
+
+    if !pages.is_empty() {
+        out += &format!(".SH SEE ALSO\n{}\n", pages.join(" ,\n"));
+    }
+
+    out
+}
+
+fn inline(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(inline)
+        .filter(|p| !p.is_empty())
+        .map(|p| escape(&p))
+        .collect::<Vec<String>>()
+        .join("\n.PP\n")
+}
+
+/// Escapes text for roff: backslashes are written as `\e` and lines that
+/// would otherwise be read as requests are guarded with `\&`.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\e")
+        .lines()
+        .map(|line| {
+            if line.starts_with('.') || line.starts_with('\'') {
+                format!("\\&{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{document, function, function_macro, header, object_macro, temp_dir};
+    use serde_json::json;
+
+    fn stdio() -> Document {
+        let mut puts = function("stdio", "puts", &["linux", "windows"]);
+        puts["associated"] = json!(["stdio/getc", "stdio/EOF", "stdio/missing"]);
+        puts["description"] = json!(".starts with a dot\n\nand a \\ backslash");
+
+        let mut getc = function_macro("stdio", "getc", &["fp"], &["linux"]);
+        getc["associated"] = json!(["stdio/puts"]);
+
+        document(
+            vec![header("stdio", &["linux", "windows"])],
+            vec![puts],
+            vec![getc, object_macro("stdio", "EOF", &["linux"])],
+            vec![],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn function_pages_have_the_standard_sections() {
+        let document = stdio();
+        let page = render_function(&document, &document.functions[0]);
+
+        assert!(page.starts_with(
+            ".TH \"CAPIBARA-PUTS\" 3 \"2024-01-01\" \"Capibara\" \"Capibara Reference\"\n"
+        ));
+        assert!(page.contains(".SH NAME\nputs \\- The puts summary.\n"));
+        assert!(
+            page.contains(".SH SYNOPSIS\n.nf\n.B #include <stdio.h>\n.PP\nint puts(void);\n.fi\n")
+        );
+        assert!(page.contains(".SH DESCRIPTION\n\\&.starts with a dot\n.PP\nand a \\e backslash\n"));
+        assert!(page.contains(".SH AVAILABILITY\nlinux, windows\n"));
+        // Function-like macros have their own page, object-like ones are
+        // covered by the header page.
+        assert!(page.ends_with(".SH SEE ALSO\n.BR capibara-getc (3) ,\n.BR capibara-stdio.h (7)\n"));
+    }
+
+    #[test]
+    fn macro_pages_see_also_their_associated_entities() {
+        let document = stdio();
+        let page = render_macro(&document, &document.macros[0]);
+
+        assert!(page.contains(".B #include <stdio.h>\n.PP\n#define getc(fp)\n"));
+        assert!(page.contains(".TP\n.I fp\nThe fp argument.\n"));
+        assert!(page.ends_with(".SH SEE ALSO\n.BR capibara-puts (3)\n"));
+    }
+
+    #[test]
+    fn function_and_macro_pages_list_their_attributes() {
+        let mut document = stdio();
+        let attributes: Attributes = serde_json::from_value(json!({
+            "thread_safety": { "level": "MT-Safe", "reasons": ["locale"] },
+            "async_signal_safety": { "level": "AS-Unsafe", "reasons": ["lock"] },
+            "cancellation_point": false,
+        }))
+        .unwrap();
+        document.functions[0].attributes = Some(attributes.clone());
+        if let MacroKind::Function(function) = &mut document.macros[0].kind {
+            function.attributes = Some(Box::new(attributes));
+        }
+
+        let section = ".SH ATTRIBUTES\n\\fIMT-Safe locale\\fP, \\fIAS-Unsafe lock\\fP, not a cancellation point\n";
+        assert!(render_function(&document, &document.functions[0]).contains(section));
+        assert!(render_macro(&document, &document.macros[0]).contains(section));
+    }
+
+    #[test]
+    fn function_and_macro_pages_list_their_errors() {
+        let mut document = stdio();
+        let errors: Vec<ErrorCondition> = serde_json::from_value(json!([
+            { "errno": "EIO", "condition": "A read\nfailed.", "ref": "stdio/EIO" },
+        ]))
+        .unwrap();
+        document.functions[0].errors = errors.clone();
+        document.functions[0].returns.convention =
+            Some(serde_json::from_value(json!({ "failure": "EOF", "sets_errno": true })).unwrap());
+        if let MacroKind::Function(function) = &mut document.macros[0].kind {
+            function.errors = errors;
+        }
+
+        let section = ".SH ERRORS\n.TP\n.B EIO\nA read failed.\n";
+        let page = render_function(&document, &document.functions[0]);
+        assert!(page.contains(&format!(
+            ".PP\nOn failure, returns \\fIEOF\\fP and sets \\fIerrno\\fP to indicate the error.\n{}",
+            section
+        )));
+        assert!(render_macro(&document, &document.macros[0]).contains(section));
+    }
+
+    #[test]
+    fn export_writes_function_and_header_pages() {
+        let root = temp_dir("man");
+        export(&stdio(), root.to_str().unwrap()).unwrap();
+
+        let mut pages: Vec<String> = ["man3", "man7"]
+            .iter()
+            .flat_map(|dir| fs::read_dir(root.join(dir)).unwrap())
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        pages.sort();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            pages,
+            vec!["capibara-getc.3", "capibara-puts.3", "capibara-stdio.h.7"]
+        );
+    }
+
+    #[test]
+    fn deprecated_entities_are_flagged_and_name_their_replacement() {
+        let mut document = stdio();
+        document.macros[0].deprecated = Some(Deprecated {
+            since: Some(String::from("C23")),
+            removed: None,
+            reason: String::from("Use <puts>."),
+            replacement: Some(String::from("stdio/puts")),
+        });
+
+        let page = render_macro(&document, &document.macros[0]);
+        assert!(page.contains(".SH DEPRECATED\nDeprecated since C23. Use <puts>.\nUse\n.BR capibara-puts (3)\ninstead.\n"));
+
+        let header = render_header(&document, &document.headers[0]);
+        assert!(header.contains("(Deprecated) "));
+    }
+
+    #[test]
+    fn function_synopses_say_what_to_link() {
+        let mut document = stdio();
+        document.functions[0].link = Some(
+            serde_json::from_value(json!({
+                "libraries": ["m"],
+                "pkg_config": ["libbsd"],
+                "os_overrides": [{ "os": ["windows"] }],
+            }))
+            .unwrap(),
+        );
+
+        let page = render_function(&document, &document.functions[0]);
+        assert!(page.contains(
+            "int puts(void);\n.fi\n.PP\nLink with \\fI-lm $(pkg-config --libs libbsd)\\fP.\n.br\nOn windows, nothing extra needs linking.\n"
+        ));
+    }
+}
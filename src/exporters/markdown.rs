@@ -0,0 +1,648 @@
+use super::{
+    attribute_terms, deprecation_summary, entities, enum_definition, feature_test_define,
+    feature_test_requirement, feature_test_synopsis, find_entity, function_signature, link_notes,
+    macro_signature, return_convention_notes, root_prefix, struct_definition, typedef_definition,
+    typedef_target, EntityRef,
+};
+use crate::{
+    definitions::{
+        _macro::{Macro, MacroKind},
+        _struct::Struct,
+        attributes::Attributes,
+        deprecated::Deprecated,
+        enumeration::Enumeration,
+        error_condition::ErrorCondition,
+        feature_test_macro::FeatureTestMacro,
+        function::{Function, Return},
+        header::{Header, HeaderSummary},
+        typedef::Typedef,
+    },
+    Document,
+};
+use std::{fs, io, path::Path};
+
+const SECTIONS: [(&str, &str); 5] = [
+    ("Function", "Functions"),
+    ("Macro", "Macros"),
+    ("Enum", "Enums"),
+    ("Struct", "Structs"),
+    ("Type", "Types"),
+];
+
+/// Writes one Markdown file per header and per entity to `output_path`,
+/// plus an `index.md` table of contents.
+pub(crate) fn export(document: &Document, output_path: &str) -> io::Result<()> {
+    let root = Path::new(output_path);
+    fs::create_dir_all(root)?;
+    fs::write(root.join("index.md"), render_index(document))?;
+
+    for header in document.headers.iter() {
+        let header_dir = root.join(&*header._ref);
+        fs::create_dir_all(&header_dir)?;
+        fs::write(header_dir.join("index.md"), render_header(document, header))?;
+    }
+
+    for function in document.functions.iter() {
+        write_entity_page(
+            root,
+            &function.header,
+            "fn-",
+            &function.name,
+            render_function(document, function),
+        )?;
+    }
+
+    for _macro in document.macros.iter() {
+        write_entity_page(
+            root,
+            &_macro.header,
+            "mo-",
+            &_macro.name,
+            render_macro(document, _macro),
+        )?;
+    }
+
+    for _enum in document.enums.iter() {
+        write_entity_page(
+            root,
+            &_enum.header,
+            "em-",
+            &_enum.name,
+            render_enum(document, _enum),
+        )?;
+    }
+
+    for _struct in document.structs.iter() {
+        write_entity_page(
+            root,
+            &_struct.header,
+            "st-",
+            &_struct.name,
+            render_struct(document, _struct),
+        )?;
+    }
+
+    for typedef in document.typedefs.iter() {
+        write_entity_page(
+            root,
+            &typedef.header,
+            "tf-",
+            &typedef.name,
+            render_typedef(document, typedef),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn write_entity_page(
+    root: &Path,
+    header: &HeaderSummary,
+    prefix: &str,
+    name: &str,
+    contents: String,
+) -> io::Result<()> {
+    let header_dir = root.join(&*header._ref);
+    fs::create_dir_all(&header_dir)?;
+    fs::write(header_dir.join(format!("{}{}.md", prefix, name)), contents)
+}
+
+fn entity_link(root: &str, entity: &EntityRef) -> String {
+    format!(
+        "[`{}`]({}{}/{}.md)",
+        entity.name,
+        root,
+        entity.header._ref,
+        entity.file_stem()
+    )
+}
+
+fn render_index(document: &Document) -> String {
+    let mut out = format!(
+        "# Capibara Reference\n\nGenerated {} from <{}>.\n\n## Contents\n\n",
+        document.build_date, document.reference_url
+    );
+
+    let all = entities(document);
+
+    for header in document.headers.iter() {
+        out += &format!(
+            "- [`{}`]({}/index.md) - {}\n",
+            header.name,
+            header._ref,
+            inline(&header.summary)
+        );
+
+        for (kind, title) in SECTIONS {
+            let section: Vec<&EntityRef> = all
+                .iter()
+                .filter(|e| e.kind == kind && e.header._ref == header._ref)
+                .collect();
+
+            if section.is_empty() {
+                continue;
+            }
+
+            let links: Vec<String> = section.iter().map(|e| entity_link("", e)).collect();
+            out += &format!("  - {}: {}\n", title, links.join(", "));
+        }
+    }
+
+    out
+}
+
+fn render_header(document: &Document, header: &Header) -> String {
+    let root = root_prefix(&header._ref);
+    let mut out = format!(
+        "# `{}`\n\n{}\n\n{}",
+        header.name,
+        header.summary,
+        os_line(&header.os_affinity)
+    );
+
+    let members: Vec<EntityRef> = entities(document)
+        .into_iter()
+        .filter(|entity| entity.header._ref == header._ref)
+        .collect();
+
+    for (kind, title) in SECTIONS {
+        let section: Vec<&EntityRef> = members.iter().filter(|m| m.kind == kind).collect();
+
+        if section.is_empty() {
+            continue;
+        }
+
+        out += &format!("\n## {}\n\n| Name | Summary |\n| --- | --- |\n", title);
+
+        for entity in section {
+            out += &format!(
+                "| {}{} | {} |\n",
+                entity_link(&root, entity),
+                if entity.deprecated.is_some() {
+                    " *(deprecated)*"
+                } else {
+                    ""
+                },
+                cell(entity.summary)
+            );
+        }
+    }
+
+    out
+}
+
+fn entity_heading(kind: &str, name: &str, header: &HeaderSummary, summary: &str) -> String {
+    format!(
+        "# `{}` ({})\n\nDefined in [`{}`](index.md).\n\n{}\n\n",
+        name, kind, header.name, summary
+    )
+}
+
+fn code_block(code: &str) -> String {
+    format!("```c\n{}\n```\n\n", code.trim_end())
+}
+
+fn render_function(document: &Document, function: &Function) -> String {
+    let root = root_prefix(&function.header._ref);
+    let mut out = entity_heading(
+        "Function",
+        &function.name,
+        &function.header,
+        &function.summary,
+    );
+    out += &deprecation_notice(document, &root, &function.deprecated);
+
+    out += &code_block(&format!(
+        "{}#include <{}>\n\n{}",
+        feature_test_define(&function.feature_test_macros),
+        function.header.name,
+        function_signature(function)
+    ));
+    if let Some(link) = &function.link {
+        let notes = link_notes(link, |arguments| format!("`{}`", arguments));
+
+        if !notes.is_empty() {
+            out += &format!("{}\n\n", notes.join(" "));
+        }
+    }
+    out += &os_line(&function.os_affinity);
+    out += &feature_test_line(document, &root, &function.feature_test_macros);
+    out += &attributes_line(function.attributes.as_ref());
+
+    if !function.parameters.is_empty() {
+        out += "\n## Parameters\n\n| Name | Type | Description |\n| --- | --- | --- |\n";
+        for parameter in function.parameters.iter() {
+            out += &format!(
+                "| `{}` | `{}` | {} |\n",
+                parameter.name,
+                parameter._type,
+                cell(&parameter.description)
+            );
+        }
+    }
+
+    out += &format!(
+        "\n## Return Value\n\n`{}`\n\n{}\n",
+        function.returns._type, function.returns.description
+    );
+    out += &return_convention_line(&function.returns);
+    out += &errors_table(document, &root, &function.errors);
+    out += &format!("\n## Description\n\n{}\n", function.description.trim_end());
+
+    if !function.examples.is_empty() {
+        out += "\n## Examples\n";
+        for example in function.examples.iter() {
+            out += &format!("\n### {}\n\n{}", example.title, code_block(&example.code));
+        }
+    }
+
+    if !function.associated.is_empty() {
+        out += "\n## See Also\n\n";
+        for reference in function.associated.iter() {
+            match find_entity(document, reference) {
+                Some(entity) => {
+                    out += &format!(
+                        "- {} - {}\n",
+                        entity_link(&root, &entity),
+                        inline(entity.summary)
+                    )
+                }
+                None => out += &format!("- `{}`\n", reference),
+            }
+        }
+    }
+
+    out
+}
+
+fn render_macro(document: &Document, _macro: &Macro) -> String {
+    let root = root_prefix(&_macro.header._ref);
+    let mut out = entity_heading("Macro", &_macro.name, &_macro.header, &_macro.summary);
+    out += &deprecation_notice(document, &root, &_macro.deprecated);
+
+    out += &code_block(&format!(
+        "{}#include <{}>\n\n{}",
+        feature_test_define(&_macro.feature_test_macros),
+        _macro.header.name,
+        macro_signature(_macro)
+    ));
+    out += &os_line(&_macro.os_affinity);
+    out += &feature_test_line(document, &root, &_macro.feature_test_macros);
+
+    if let MacroKind::Function(function) = &_macro.kind {
+        out += &attributes_line(function.attributes.as_deref());
+
+        if !function.parameters.is_empty() {
+            out += "\n## Parameters\n\n| Name | Description |\n| --- | --- |\n";
+            for parameter in function.parameters.iter() {
+                out += &format!(
+                    "| `{}` | {} |\n",
+                    parameter.name,
+                    cell(&parameter.description)
+                );
+            }
+        }
+
+        out += &format!(
+            "\n## Return Value\n\n`{}`\n\n{}\n",
+            function.returns._type, function.returns.description
+        );
+        out += &return_convention_line(&function.returns);
+        out += &errors_table(document, &root, &function.errors);
+    }
+
+    out += &format!("\n## Description\n\n{}\n", _macro.description.trim_end());
+
+    if let MacroKind::Function(function) = &_macro.kind {
+        if !function.examples.is_empty() {
+            out += "\n## Examples\n";
+            for example in function.examples.iter() {
+                out += &format!("\n### {}\n\n{}", example.title, code_block(&example.code));
+            }
+        }
+    }
+
+    out
+}
+
+fn render_enum(document: &Document, _enum: &Enumeration) -> String {
+    let root = root_prefix(&_enum.header._ref);
+    let mut out = entity_heading("Enum", &_enum.name, &_enum.header, &_enum.summary);
+    out += &deprecation_notice(document, &root, &_enum.deprecated);
+
+    out += &code_block(&enum_definition(_enum));
+    out += &os_line(&_enum.os_affinity);
+
+    if !_enum.variants.is_empty() {
+        out += "\n## Variants\n\n| Name | Description |\n| --- | --- |\n";
+        for variant in _enum.variants.iter() {
+            out += &format!("| `{}` | {} |\n", variant.name, cell(&variant.description));
+        }
+    }
+
+    out += &format!("\n## Description\n\n{}\n", _enum.description.trim_end());
+
+    out
+}
+
+fn render_struct(document: &Document, _struct: &Struct) -> String {
+    let root = root_prefix(&_struct.header._ref);
+    let mut out = entity_heading("Struct", &_struct.name, &_struct.header, &_struct.summary);
+    out += &deprecation_notice(document, &root, &_struct.deprecated);
+
+    out += &code_block(&feature_test_synopsis(
+        &_struct.header,
+        &_struct.feature_test_macros,
+        &struct_definition(_struct),
+    ));
+    out += &os_line(&_struct.os_affinity);
+    out += &feature_test_line(document, &root, &_struct.feature_test_macros);
+
+    if !_struct.fields.is_empty() {
+        out += "\n## Fields\n\n| Name | Type | Description |\n| --- | --- | --- |\n";
+        for field in _struct.fields.iter() {
+            out += &format!(
+                "| `{}` | `{}` | {} |\n",
+                field.name,
+                field._type,
+                cell(&field.description)
+            );
+        }
+    }
+
+    out += &format!("\n## Description\n\n{}\n", _struct.description.trim_end());
+
+    out
+}
+
+fn render_typedef(document: &Document, typedef: &Typedef) -> String {
+    let root = root_prefix(&typedef.header._ref);
+    let mut out = entity_heading("Type", &typedef.name, &typedef.header, &typedef.summary);
+    out += &deprecation_notice(document, &root, &typedef.deprecated);
+
+    out += &code_block(&feature_test_synopsis(
+        &typedef.header,
+        &typedef.feature_test_macros,
+        &typedef_definition(typedef),
+    ));
+    out += &os_line(&typedef.os_affinity);
+    out += &feature_test_line(document, &root, &typedef.feature_test_macros);
+
+    if let Some(target) = typedef_target(typedef) {
+        out += &format!(
+            "\n## Aliases\n\n{} from `{}`\n",
+            entity_link(&root, &target),
+            target.header.name
+        );
+    }
+
+    out += &format!("\n## Description\n\n{}\n", typedef.description.trim_end());
+
+    out
+}
+
+fn deprecation_notice(document: &Document, root: &str, deprecated: &Option<Deprecated>) -> String {
+    let deprecated = match deprecated {
+        Some(deprecated) => deprecated,
+        None => return String::new(),
+    };
+
+    let replacement = match &deprecated.replacement {
+        Some(replacement) => match find_entity(document, replacement) {
+            Some(entity) => format!(" Use {} instead.", entity_link(root, &entity)),
+            None => format!(" Use `{}` instead.", replacement),
+        },
+        None => String::new(),
+    };
+
+    format!(
+        "> **Warning:** {}{}\n\n",
+        inline(&deprecation_summary(deprecated)),
+        replacement
+    )
+}
+
+fn feature_test_line(
+    document: &Document,
+    root: &str,
+    feature_test_macros: &[FeatureTestMacro],
+) -> String {
+    if feature_test_macros.is_empty() {
+        return String::new();
+    }
+
+    let requirements: Vec<String> = feature_test_macros
+        .iter()
+        .map(|feature_test_macro| {
+            let requirement = feature_test_requirement(feature_test_macro);
+
+            match feature_test_macro
+                ._ref
+                .as_deref()
+                .and_then(|reference| find_entity(document, reference))
+            {
+                Some(entity) => format!(
+                    "[`{}`]({}{}/{}.md)",
+                    requirement,
+                    root,
+                    entity.header._ref,
+                    entity.file_stem()
+                ),
+                None => format!("`{}`", requirement),
+            }
+        })
+        .collect();
+
+    format!("\n**Feature test macros:** {}\n", requirements.join(" or "))
+}
+
+fn attributes_line(attributes: Option<&Attributes>) -> String {
+    let terms = match attributes {
+        Some(attributes) => attribute_terms(attributes, |term| format!("`{}`", term)),
+        None => return String::new(),
+    };
+
+    if terms.is_empty() {
+        String::new()
+    } else {
+        format!("\n**Attributes:** {}\n", terms.join(", "))
+    }
+}
+
+fn os_line(os_affinity: &[String]) -> String {
+    let oses: Vec<String> = os_affinity.iter().map(|os| format!("`{}`", os)).collect();
+
+    format!("**OS:** {}\n", oses.join(" "))
+}
+
+/// Collapses text onto a single line for list items.
+fn inline(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// The sentences of a return convention, as a paragraph.
+fn return_convention_line(returns: &Return) -> String {
+    match &returns.convention {
+        Some(convention) => {
+            let notes = return_convention_notes(convention, |value| format!("`{}`", value));
+
+            if notes.is_empty() {
+                String::new()
+            } else {
+                format!("\n{}\n", notes.join(" "))
+            }
+        }
+        None => String::new(),
+    }
+}
+
+/// The errno values a function fails with, linked to their macro pages.
+fn errors_table(document: &Document, root: &str, errors: &[ErrorCondition]) -> String {
+    if errors.is_empty() {
+        return String::new();
+    }
+
+    let mut out = String::from("\n## Errors\n\n| Error | Condition |\n| --- | --- |\n");
+
+    for error in errors.iter() {
+        let errno = match error._ref.as_ref().and_then(|r| find_entity(document, r)) {
+            Some(entity) => entity_link(root, &entity),
+            None => format!("`{}`", error.errno),
+        };
+
+        out += &format!("| {} | {} |\n", errno, cell(&error.condition));
+    }
+
+    out
+}
+
+/// Makes text safe to place inside a Markdown table cell.
+fn cell(text: &str) -> String {
+    inline(text).replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{document, function, header, object_macro, temp_dir};
+    use serde_json::json;
+
+    fn stdio() -> Document {
+        let mut puts = function("stdio", "puts", &["linux", "windows"]);
+        puts["parameters"] =
+            json!([{ "name": "s", "type": "const char *", "description": "A line | or two." }]);
+        puts["associated"] = json!(["stdio/EOF", "stdio/missing"]);
+
+        document(
+            vec![header("stdio", &["linux", "windows"])],
+            vec![puts],
+            vec![object_macro("stdio", "EOF", &["linux"])],
+            vec![],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn index_lists_each_header_with_its_entities() {
+        let index = render_index(&stdio());
+
+        assert!(index.contains("- [`stdio.h`](stdio/index.md) - The stdio header.\n"));
+        assert!(index.contains("  - Functions: [`puts`](stdio/fn-puts.md)\n"));
+        assert!(index.contains("  - Macros: [`EOF`](stdio/mo-EOF.md)\n"));
+    }
+
+    #[test]
+    fn function_pages_show_the_synopsis_parameters_and_links() {
+        let document = stdio();
+        let page = render_function(&document, &document.functions[0]);
+
+        assert!(page.contains("```c\n#include <stdio.h>\n\nint puts(const char *s);\n```\n"));
+        assert!(page.contains("**OS:** `linux` `windows`\n"));
+        assert!(page.contains("| `s` | `const char *` | A line \\| or two. |\n"));
+        assert!(page.contains("- [`EOF`](../stdio/mo-EOF.md) - The EOF summary.\n"));
+        assert!(page.contains("- `stdio/missing`\n"));
+    }
+
+    #[test]
+    fn export_writes_a_file_per_header_and_entity() {
+        let root = temp_dir("markdown");
+        export(&stdio(), root.to_str().unwrap()).unwrap();
+
+        for page in [
+            "index.md",
+            "stdio/index.md",
+            "stdio/fn-puts.md",
+            "stdio/mo-EOF.md",
+        ] {
+            assert!(root.join(page).is_file(), "{}", page);
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn deprecated_entities_are_flagged_and_link_their_replacement() {
+        let mut document = stdio();
+        document.macros[0].deprecated = Some(Deprecated {
+            since: Some(String::from("C23")),
+            removed: None,
+            reason: String::from("Use <puts>."),
+            replacement: Some(String::from("stdio/puts")),
+        });
+
+        let page = render_macro(&document, &document.macros[0]);
+        assert!(page.contains("> **Warning:** Deprecated since C23. Use <puts>. Use [`puts`](../stdio/fn-puts.md) instead."));
+
+        let index = render_header(&document, &document.headers[0]);
+        assert!(index.contains("[`EOF`](../stdio/mo-EOF.md) *(deprecated)*"));
+    }
+
+    #[test]
+    fn function_pages_list_their_attributes() {
+        let mut document = stdio();
+        document.functions[0].attributes = Some(
+            serde_json::from_value(json!({
+                "thread_safety": { "level": "MT-Unsafe", "reasons": ["race:stdout"] },
+                "cancellation_point": true,
+            }))
+            .unwrap(),
+        );
+
+        let page = render_function(&document, &document.functions[0]);
+        assert!(page.contains("**Attributes:** `MT-Unsafe race:stdout`, cancellation point\n"));
+    }
+
+    #[test]
+    fn function_synopses_say_what_to_link() {
+        let mut document = stdio();
+        document.functions[0].link = Some(
+            serde_json::from_value(json!({
+                "libraries": ["m"],
+                "pkg_config": ["libbsd"],
+                "os_overrides": [{ "os": ["windows"] }],
+            }))
+            .unwrap(),
+        );
+
+        let page = render_function(&document, &document.functions[0]);
+        assert!(page.contains(
+            "Link with `-lm $(pkg-config --libs libbsd)`. On windows, nothing extra needs linking.\n"
+        ));
+    }
+
+    #[test]
+    fn function_pages_state_the_return_convention_and_link_errors() {
+        let mut document = stdio();
+        document.functions[0].returns.convention =
+            Some(serde_json::from_value(json!({ "success": "0", "failure": "EOF" })).unwrap());
+        document.functions[0].errors = serde_json::from_value(json!([
+            { "errno": "EOF", "condition": "The stream ended.", "ref": "stdio/EOF" },
+            { "errno": "EIO", "condition": "A write | read failed." },
+        ]))
+        .unwrap();
+
+        let page = render_function(&document, &document.functions[0]);
+        assert!(page.contains("\nOn success, returns `0`. On failure, returns `EOF`.\n"));
+        assert!(page.contains("| [`EOF`](../stdio/mo-EOF.md) | The stream ended. |\n"));
+        assert!(page.contains("| `EIO` | A write \\| read failed. |\n"));
+    }
+}
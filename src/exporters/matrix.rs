@@ -0,0 +1,296 @@
+use super::{entities, html::escape, EntityRef};
+use crate::{definitions::header::Header, vocabulary::Vocabulary, Document};
+use std::{fs, io, path::Path};
+
+const STYLESHEET: &str = r#"body { font-family: sans-serif; margin: 1em; color: #222; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ddd; padding: 0.3em 0.6em; text-align: left; }
+th { background: #f4f4f4; }
+tbody th { background: #e8eef7; }
+td.yes { background: #d9f2d9; text-align: center; }
+td.no { background: #f7dede; text-align: center; }
+tr.total td { font-weight: bold; background: #f4f4f4; text-align: center; }
+tr.total td.unsupported { color: #999; font-weight: normal; }
+.kind { color: #777; font-size: 0.8em; }
+.deprecated { color: #c0392b; font-size: 0.8em; }
+"#;
+
+/// Writes `matrix.csv` and `matrix.html` to `output_path`: one row per
+/// entity (flagged when deprecated) and one column per OS, grouped by
+/// header, with a totals row per header counting the entities available on
+/// each OS the header supports.
+pub(crate) fn export(
+    document: &Document,
+    os_vocabulary: &Vocabulary,
+    output_path: &str,
+) -> io::Result<()> {
+    let root = Path::new(output_path);
+    fs::create_dir_all(root)?;
+
+    let columns = columns(document, os_vocabulary);
+
+    fs::write(root.join("matrix.csv"), render_csv(document, &columns))?;
+    fs::write(root.join("matrix.html"), render_html(document, &columns))
+}
+
+/// The OSes to show, in canonical order: the build's `--os` filter, or
+/// every OS some header supports.
+fn columns(document: &Document, os_vocabulary: &Vocabulary) -> Vec<String> {
+    os_vocabulary
+        .names()
+        .into_iter()
+        .filter(|os| {
+            if document.os_filter.is_empty() {
+                document
+                    .headers
+                    .iter()
+                    .any(|h| h.os_affinity.iter().any(|a| a == os))
+            } else {
+                document.os_filter.iter().any(|f| f == os)
+            }
+        })
+        .map(String::from)
+        .collect()
+}
+
+fn header_entities<'a>(document: &'a Document, header: &Header) -> Vec<EntityRef<'a>> {
+    entities(document)
+        .into_iter()
+        .filter(|e| e.header._ref == header._ref)
+        .collect()
+}
+
+/// Per-OS total for a header: `available/total`, or `None` when the
+/// header's merged affinity doesn't include the OS.
+fn header_total(header: &Header, entities: &[EntityRef], os: &str) -> Option<String> {
+    if !header.os_affinity.iter().any(|a| a == os) {
+        return None;
+    }
+
+    let available = entities
+        .iter()
+        .filter(|e| e.os_affinity.iter().any(|a| a == os))
+        .count();
+
+    Some(format!("{}/{}", available, entities.len()))
+}
+
+fn render_csv(document: &Document, columns: &[String]) -> String {
+    let mut out = csv_row(
+        ["header", "kind", "name", "deprecated"]
+            .iter()
+            .map(|c| c.to_string())
+            .chain(columns.iter().cloned()),
+    );
+
+    for header in document.headers.iter() {
+        let entities = header_entities(document, header);
+
+        for entity in entities.iter() {
+            out += &csv_row(
+                [
+                    header.name.to_string(),
+                    entity.kind.to_string(),
+                    entity.name.to_string(),
+                    if entity.deprecated.is_some() {
+                        String::from("yes")
+                    } else {
+                        String::new()
+                    },
+                ]
+                .into_iter()
+                .chain(columns.iter().map(|os| {
+                    if entity.os_affinity.contains(os) {
+                        String::from("yes")
+                    } else {
+                        String::from("no")
+                    }
+                })),
+            );
+        }
+
+        out += &csv_row(
+            [
+                header.name.to_string(),
+                String::from("Total"),
+                String::new(),
+                String::new(),
+            ]
+            .into_iter()
+            .chain(
+                columns
+                    .iter()
+                    .map(|os| header_total(header, &entities, os).unwrap_or_default()),
+            ),
+        );
+    }
+
+    out
+}
+
+fn csv_row(cells: impl Iterator<Item = String>) -> String {
+    let cells: Vec<String> = cells
+        .map(|cell| {
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell
+            }
+        })
+        .collect();
+
+    cells.join(",") + "\r\n"
+}
+
+fn render_html(document: &Document, columns: &[String]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>OS Compatibility</title>\n<style>\n{}</style>\n</head>\n<body>\n<h1>OS Compatibility</h1>\n<table>\n<thead><tr><th>Name</th>",
+        STYLESHEET
+    );
+
+    for os in columns.iter() {
+        out += &format!("<th>{}</th>", escape(os));
+    }
+    out += "</tr></thead>\n";
+
+    for header in document.headers.iter() {
+        let entities = header_entities(document, header);
+
+        out += &format!(
+            "<tbody>\n<tr><th colspan=\"{}\">{}</th></tr>\n",
+            columns.len() + 1,
+            escape(&header.name)
+        );
+
+        for entity in entities.iter() {
+            out += &format!(
+                "<tr><td>{} <span class=\"kind\">{}</span>{}</td>",
+                escape(entity.name),
+                entity.kind,
+                if entity.deprecated.is_some() {
+                    " <span class=\"deprecated\">deprecated</span>"
+                } else {
+                    ""
+                }
+            );
+            for os in columns.iter() {
+                if entity.os_affinity.contains(os) {
+                    out += "<td class=\"yes\">&#10003;</td>";
+                } else {
+                    out += "<td class=\"no\">&#10007;</td>";
+                }
+            }
+            out += "</tr>\n";
+        }
+
+        out += "<tr class=\"total\"><td>Total</td>";
+        for os in columns.iter() {
+            match header_total(header, &entities, os) {
+                Some(total) => out += &format!("<td>{}</td>", total),
+                None => out += "<td class=\"unsupported\">&ndash;</td>",
+            }
+        }
+        out += "</tr>\n</tbody>\n";
+    }
+
+    out += &format!(
+        "</table>\n<footer>Built {}</footer>\n</body>\n</html>\n",
+        escape(&document.build_date)
+    );
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{document, function, header, object_macro};
+    use serde_json::json;
+
+    fn matrix_document() -> Document {
+        let mut getline = function("stdio", "getline", &["linux"]);
+        getline["deprecated"] = json!({ "reason": "Use fgets." });
+
+        document(
+            vec![
+                header("stdio", &["linux", "windows"]),
+                header("unistd", &["linux"]),
+            ],
+            vec![
+                function("stdio", "puts", &["linux", "windows"]),
+                getline,
+                function("unistd", "fork", &["linux"]),
+            ],
+            vec![object_macro("stdio", "EOF", &["windows", "linux"])],
+            vec![],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn columns_follow_the_vocabulary_order_or_the_filter() {
+        let os_vocabulary = Vocabulary::load_os("/nonexistent");
+        let mut document = matrix_document();
+
+        assert_eq!(columns(&document, &os_vocabulary), vec!["linux", "windows"]);
+
+        document.os_filter = vec![String::from("windows"), String::from("macos")];
+        assert_eq!(columns(&document, &os_vocabulary), vec!["macos", "windows"]);
+    }
+
+    #[test]
+    fn totals_count_entities_on_oses_the_header_supports() {
+        let document = matrix_document();
+        let stdio = &document.headers[0];
+        let entities = header_entities(&document, stdio);
+
+        assert_eq!(entities.len(), 3);
+        assert_eq!(
+            header_total(stdio, &entities, "linux").as_deref(),
+            Some("3/3")
+        );
+        assert_eq!(
+            header_total(stdio, &entities, "windows").as_deref(),
+            Some("2/3")
+        );
+        assert_eq!(header_total(stdio, &entities, "macos"), None);
+    }
+
+    #[test]
+    fn csv_has_a_row_per_entity_and_a_total_per_header() {
+        let document = matrix_document();
+        let columns = vec![String::from("linux"), String::from("windows")];
+        let csv = render_csv(&document, &columns);
+        let rows: Vec<&str> = csv.split_terminator("\r\n").collect();
+
+        assert_eq!(rows[0], "header,kind,name,deprecated,linux,windows");
+        assert!(rows.contains(&"stdio.h,Function,getline,yes,yes,no"));
+        assert!(rows.contains(&"stdio.h,Function,puts,,yes,yes"));
+        assert!(rows.contains(&"stdio.h,Total,,,3/3,2/3"));
+        assert!(rows.contains(&"unistd.h,Total,,,1/1,"));
+        assert_eq!(rows.len(), 1 + 3 + 1 + 1 + 1);
+    }
+
+    #[test]
+    fn csv_cells_are_quoted_when_needed() {
+        assert_eq!(
+            csv_row(["plain", "a,b", "say \"hi\""].iter().map(|c| c.to_string())),
+            "plain,\"a,b\",\"say \"\"hi\"\"\"\r\n"
+        );
+    }
+
+    #[test]
+    fn html_marks_support_and_unsupported_totals() {
+        let document = matrix_document();
+        let columns = vec![String::from("linux"), String::from("windows")];
+        let html = render_html(&document, &columns);
+
+        assert!(html.contains("<th>linux</th><th>windows</th>"));
+        assert!(html.contains(
+            "<tr><td>getline <span class=\"kind\">Function</span> <span class=\"deprecated\">deprecated</span></td><td class=\"yes\">&#10003;</td><td class=\"no\">&#10007;</td></tr>"
+        ));
+        assert!(html.contains(
+            "<tr class=\"total\"><td>Total</td><td>1/1</td><td class=\"unsupported\">&ndash;</td></tr>"
+        ));
+    }
+}
@@ -0,0 +1,470 @@
+pub mod c_header;
+pub mod docset;
+pub mod html;
+pub mod man;
+pub mod markdown;
+pub mod matrix;
+pub mod search;
+pub mod sqlite;
+
+use crate::{
+    definitions::{
+        _macro::{Macro, MacroKind},
+        _struct::Struct,
+        attributes::Attributes,
+        availability::Availability,
+        deprecated::Deprecated,
+        enumeration::Enumeration,
+        feature_test_macro::FeatureTestMacro,
+        function::{Function, ReturnConvention},
+        header::HeaderSummary,
+        link::{Link, LinkLibraries},
+        standard::Standard,
+        typedef::{Typedef, TypedefRef},
+    },
+    Document,
+};
+
+/// A lightweight view over any documented entity, used by the exporters to
+/// build indexes and resolve `header/name` references.
+#[derive(Clone, Copy)]
+pub(crate) struct EntityRef<'a> {
+    pub prefix: &'static str,
+    pub kind: &'static str,
+    pub header: &'a HeaderSummary,
+    pub name: &'a str,
+    pub summary: &'a str,
+    pub os_affinity: &'a [String],
+    pub arch_affinity: &'a [String],
+    pub standards: &'a [Standard],
+    pub deprecated: Option<&'a Deprecated>,
+    pub availability: &'a [Availability],
+}
+
+impl EntityRef<'_> {
+    /// File stem used for the entity, mirroring the corpus file naming
+    /// (`fn-printf`, `st-tm`, ...).
+    pub fn file_stem(&self) -> String {
+        format!("{}{}", self.prefix, self.name)
+    }
+}
+
+pub(crate) fn entities(document: &Document) -> Vec<EntityRef<'_>> {
+    let mut entities = Vec::new();
+
+    for function in document.functions.iter() {
+        entities.push(EntityRef {
+            prefix: "fn-",
+            kind: "Function",
+            header: &function.header,
+            name: &function.name,
+            summary: &function.summary,
+            os_affinity: &function.os_affinity,
+            arch_affinity: &function.arch_affinity,
+            standards: &function.standards,
+            deprecated: function.deprecated.as_ref(),
+            availability: &function.availability,
+        });
+    }
+
+    for _macro in document.macros.iter() {
+        entities.push(EntityRef {
+            prefix: "mo-",
+            kind: "Macro",
+            header: &_macro.header,
+            name: &_macro.name,
+            summary: &_macro.summary,
+            os_affinity: &_macro.os_affinity,
+            arch_affinity: &_macro.arch_affinity,
+            standards: &_macro.standards,
+            deprecated: _macro.deprecated.as_ref(),
+            availability: &_macro.availability,
+        });
+    }
+
+    for _enum in document.enums.iter() {
+        entities.push(EntityRef {
+            prefix: "em-",
+            kind: "Enum",
+            header: &_enum.header,
+            name: &_enum.name,
+            summary: &_enum.summary,
+            os_affinity: &_enum.os_affinity,
+            arch_affinity: &_enum.arch_affinity,
+            standards: &_enum.standards,
+            deprecated: _enum.deprecated.as_ref(),
+            availability: &_enum.availability,
+        });
+    }
+
+    for _struct in document.structs.iter() {
+        entities.push(EntityRef {
+            prefix: "st-",
+            kind: "Struct",
+            header: &_struct.header,
+            name: &_struct.name,
+            summary: &_struct.summary,
+            os_affinity: &_struct.os_affinity,
+            arch_affinity: &_struct.arch_affinity,
+            standards: &_struct.standards,
+            deprecated: _struct.deprecated.as_ref(),
+            availability: &_struct.availability,
+        });
+    }
+
+    for typedef in document.typedefs.iter() {
+        entities.push(EntityRef {
+            prefix: "tf-",
+            kind: "Type",
+            header: &typedef.header,
+            name: &typedef.name,
+            summary: &typedef.summary,
+            os_affinity: &typedef.os_affinity,
+            arch_affinity: &typedef.arch_affinity,
+            standards: &typedef.standards,
+            deprecated: typedef.deprecated.as_ref(),
+            availability: &typedef.availability,
+        });
+    }
+
+    entities
+}
+
+/// Splits a `header/name` reference the same way typedef `associated_ref`
+/// values are split during discovery.
+pub(crate) fn split_ref(reference: &str) -> Option<(&str, &str)> {
+    reference
+        .rsplit_once('/')
+        .filter(|(header, name)| !header.is_empty() && !name.is_empty())
+}
+
+pub(crate) fn find_entity<'a>(document: &'a Document, reference: &str) -> Option<EntityRef<'a>> {
+    let (header_ref, name) = split_ref(reference)?;
+
+    entities(document)
+        .into_iter()
+        .find(|entity| entity.header._ref.as_str() == header_ref && entity.name == name)
+}
+
+/// The documented structs, enums and typedefs a C type string names:
+/// `struct tm *` names the struct `tm`, `time_t` the typedef `time_t`.
+pub(crate) fn referenced_types<'a>(document: &'a Document, _type: &str) -> Vec<EntityRef<'a>> {
+    let words: Vec<&str> = _type
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let entities = entities(document);
+    let mut referenced: Vec<EntityRef> = Vec::new();
+
+    for (i, word) in words.iter().enumerate() {
+        let kind = match if i > 0 { words[i - 1] } else { "" } {
+            "struct" => "Struct",
+            "enum" => "Enum",
+            _ => "Type",
+        };
+
+        if let Some(entity) = entities.iter().find(|e| e.kind == kind && e.name == *word) {
+            if !referenced
+                .iter()
+                .any(|r| r.kind == entity.kind && r.name == entity.name)
+            {
+                referenced.push(*entity);
+            }
+        }
+    }
+
+    referenced
+}
+
+/// Resolves a typedef's `associated_ref` to the enum or struct it aliases.
+pub(crate) fn typedef_target(typedef: &Typedef) -> Option<EntityRef<'_>> {
+    match &typedef.associated_ref {
+        TypedefRef::None(_) => None,
+        TypedefRef::Enumeration(e) => Some(EntityRef {
+            prefix: "em-",
+            kind: "Enum",
+            header: &e.header,
+            name: &e.name,
+            summary: &e.summary,
+            os_affinity: &e.os_affinity,
+            arch_affinity: &e.arch_affinity,
+            standards: &e.standards,
+            deprecated: e.deprecated.as_ref(),
+            availability: &e.availability,
+        }),
+        TypedefRef::Struct(s) => Some(EntityRef {
+            prefix: "st-",
+            kind: "Struct",
+            header: &s.header,
+            name: &s.name,
+            summary: &s.summary,
+            os_affinity: &s.os_affinity,
+            arch_affinity: &s.arch_affinity,
+            standards: &s.standards,
+            deprecated: s.deprecated.as_ref(),
+            availability: &s.availability,
+        }),
+    }
+}
+
+/// Relative path from a page inside `header_ref` back to the output root.
+pub(crate) fn root_prefix(header_ref: &str) -> String {
+    "../".repeat(header_ref.split('/').count())
+}
+
+/// The deprecation notice exporters show, without the replacement, which
+/// each links in its own format (`Deprecated since C99, removed in C11.
+/// Cannot limit how much it reads.`).
+pub(crate) fn deprecation_summary(deprecated: &Deprecated) -> String {
+    let mut out = String::from("Deprecated");
+
+    if let Some(since) = &deprecated.since {
+        out += &format!(" since {}", since);
+    }
+
+    if let Some(removed) = &deprecated.removed {
+        out += &format!(", removed in {}", removed);
+    }
+
+    format!("{}. {}", out, deprecated.reason.trim())
+}
+
+/// `_POSIX_C_SOURCE >= 200809L`, or just the name for macros that only
+/// have to be defined.
+pub(crate) fn feature_test_requirement(feature_test_macro: &FeatureTestMacro) -> String {
+    match &feature_test_macro.value {
+        Some(value) => format!("{} >= {}", feature_test_macro.name, value),
+        None => feature_test_macro.name.clone(),
+    }
+}
+
+/// The `#define` a synopsis puts before its `#include`: the first feature
+/// test macro listed, with the alternatives in a comment.
+pub(crate) fn feature_test_define(feature_test_macros: &[FeatureTestMacro]) -> String {
+    let (preferred, alternatives) = match feature_test_macros.split_first() {
+        Some(split) => split,
+        None => return String::new(),
+    };
+
+    let mut out = format!("#define {}", preferred.name);
+
+    if let Some(value) = &preferred.value {
+        out += &format!(" {}", value);
+    }
+
+    if !alternatives.is_empty() {
+        let alternatives: Vec<String> = alternatives.iter().map(feature_test_requirement).collect();
+        out += &format!(" /* or {} */", alternatives.join(", "));
+    }
+
+    out + "\n"
+}
+
+/// A declaration preceded by the `#define` and `#include` it needs when the
+/// entity has feature test macros, or on its own when it has none.
+pub(crate) fn feature_test_synopsis(
+    header: &HeaderSummary,
+    feature_test_macros: &[FeatureTestMacro],
+    declaration: &str,
+) -> String {
+    if feature_test_macros.is_empty() {
+        return declaration.to_string();
+    }
+
+    format!(
+        "{}#include <{}>\n\n{}",
+        feature_test_define(feature_test_macros),
+        header.name,
+        declaration
+    )
+}
+
+/// Compiler arguments that link `libraries`: `-lm -pthread
+/// $(pkg-config --libs libbsd)`. Empty when there is nothing to link.
+pub(crate) fn link_arguments(libraries: &LinkLibraries) -> String {
+    let mut arguments: Vec<String> = libraries
+        .libraries
+        .iter()
+        .map(|library| format!("-l{}", library))
+        .collect();
+
+    arguments.extend(libraries.flags.iter().cloned());
+
+    if !libraries.pkg_config.is_empty() {
+        arguments.push(format!(
+            "$(pkg-config --libs {})",
+            libraries.pkg_config.join(" ")
+        ));
+    }
+
+    arguments.join(" ")
+}
+
+/// The sentences a synopsis ends with to say what to link, one for the
+/// default and one per OS override. `code` marks up the arguments in the
+/// exporter's format.
+pub(crate) fn link_notes(link: &Link, code: impl Fn(&str) -> String) -> Vec<String> {
+    let mut notes = Vec::new();
+    let arguments = link_arguments(&link.libraries);
+
+    if !arguments.is_empty() {
+        notes.push(format!("Link with {}.", code(&arguments)));
+    }
+
+    for os_link in link.os_overrides.iter() {
+        let arguments = link_arguments(&os_link.libraries);
+
+        notes.push(if arguments.is_empty() {
+            format!("On {}, nothing extra needs linking.", os_link.os.join(", "))
+        } else {
+            format!(
+                "On {}, link with {}.",
+                os_link.os.join(", "),
+                code(&arguments)
+            )
+        });
+    }
+
+    notes
+}
+
+/// An entity's attributes as the glibc manual writes them: each safety
+/// level followed by its remarks (`MT-Safe locale`), then whether it is a
+/// cancellation point. `code` marks up the levels in the exporter's format.
+pub(crate) fn attribute_terms(
+    attributes: &Attributes,
+    code: impl Fn(&str) -> String,
+) -> Vec<String> {
+    let mut terms: Vec<String> = [
+        &attributes.thread_safety,
+        &attributes.async_signal_safety,
+        &attributes.async_cancel_safety,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|safety| {
+        let mut term = safety.level.clone();
+        for reason in safety.reasons.iter() {
+            term += " ";
+            term += reason;
+        }
+        code(&term)
+    })
+    .collect();
+
+    match attributes.cancellation_point {
+        Some(true) => terms.push(String::from("cancellation point")),
+        Some(false) => terms.push(String::from("not a cancellation point")),
+        None => {}
+    }
+
+    terms
+}
+
+/// The sentences describing a return convention. `code` marks up values in
+/// the exporter's format.
+pub(crate) fn return_convention_notes(
+    convention: &ReturnConvention,
+    code: impl Fn(&str) -> String,
+) -> Vec<String> {
+    let mut notes = Vec::new();
+
+    if let Some(success) = &convention.success {
+        notes.push(format!("On success, returns {}.", code(success)));
+    }
+
+    if let Some(failure) = &convention.failure {
+        notes.push(if convention.sets_errno {
+            format!(
+                "On failure, returns {} and sets {} to indicate the error.",
+                code(failure),
+                code("errno")
+            )
+        } else {
+            format!("On failure, returns {}.", code(failure))
+        });
+    }
+
+    notes
+}
+
+/// Joins a C type and a declarator so that pointer types read naturally
+/// (`const char *format` rather than `const char * format`). Array and
+/// function pointer types get the name spliced in where C expects it
+/// (`char name[16]`, `void (*handler)(int)`).
+pub(crate) fn c_declaration(_type: &str, name: &str) -> String {
+    let _type = _type.trim();
+
+    if _type.is_empty() {
+        name.to_string()
+    } else if let Some(index) = _type.find("(*)") {
+        format!("{}(*{}){}", &_type[..index], name, &_type[index + 3..])
+    } else if let (true, Some(index)) = (_type.ends_with(']'), _type.find('[')) {
+        format!(
+            "{}{}",
+            c_declaration(&_type[..index], name),
+            &_type[index..]
+        )
+    } else if _type.ends_with('*') {
+        format!("{}{}", _type, name)
+    } else {
+        format!("{} {}", _type, name)
+    }
+}
+
+pub(crate) fn function_signature(function: &Function) -> String {
+    let parameters: Vec<String> = function
+        .parameters
+        .iter()
+        .map(|p| c_declaration(&p._type, &p.name))
+        .collect();
+
+    format!(
+        "{}({});",
+        c_declaration(&function.returns._type, &function.name),
+        if parameters.is_empty() {
+            String::from("void")
+        } else {
+            parameters.join(", ")
+        }
+    )
+}
+
+pub(crate) fn macro_signature(_macro: &Macro) -> String {
+    match &_macro.kind {
+        MacroKind::Object(_) => format!("#define {}", _macro.name),
+        MacroKind::Function(function) => {
+            let parameters: Vec<&str> = function
+                .parameters
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect();
+
+            format!("#define {}({})", _macro.name, parameters.join(", "))
+        }
+    }
+}
+
+pub(crate) fn enum_definition(_enum: &Enumeration) -> String {
+    let variants: Vec<String> = _enum
+        .variants
+        .iter()
+        .map(|v| format!("    {},", v.name))
+        .collect();
+
+    format!("enum {} {{\n{}\n}};", _enum.name, variants.join("\n"))
+}
+
+pub(crate) fn struct_definition(_struct: &Struct) -> String {
+    let fields: Vec<String> = _struct
+        .fields
+        .iter()
+        .map(|f| format!("    {};", c_declaration(&f._type, &f.name)))
+        .collect();
+
+    format!("struct {} {{\n{}\n}};", _struct.name, fields.join("\n"))
+}
+
+pub(crate) fn typedef_definition(typedef: &Typedef) -> String {
+    format!("typedef {};", c_declaration(&typedef._type, &typedef.name))
+}
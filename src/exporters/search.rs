@@ -0,0 +1,194 @@
+use super::entities;
+use crate::{definitions::_macro::MacroKind, Document};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Ranking weight each field contributes to a term's score.
+const NAME_WEIGHT: u32 = 10;
+const PARAMETER_WEIGHT: u32 = 3;
+const SUMMARY_WEIGHT: u32 = 2;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "is", "it", "of", "on",
+    "or", "the", "to", "with",
+];
+
+/// A compact inverted index over the document for client-side search.
+///
+/// `terms` is sorted so that prefix queries can binary search for the first
+/// matching term and walk forward; `postings[i]` holds flattened
+/// `[document, score, document, score, ...]` pairs for `terms[i]`.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SearchIndex {
+    pub weights: SearchWeights,
+    pub documents: Vec<SearchDocument>,
+    pub terms: Vec<String>,
+    pub postings: Vec<Vec<u32>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SearchWeights {
+    pub name: u32,
+    pub parameter: u32,
+    pub summary: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SearchDocument {
+    pub kind: String,
+    pub name: String,
+    pub header: String,
+    pub path: String,
+    pub deprecated: bool,
+}
+
+pub(crate) fn build(document: &Document) -> SearchIndex {
+    let mut documents = Vec::new();
+    let mut index: BTreeMap<String, BTreeMap<u32, u32>> = BTreeMap::new();
+
+    for entity in entities(document) {
+        let id = documents.len() as u32;
+
+        documents.push(SearchDocument {
+            kind: entity.kind.to_string(),
+            name: entity.name.to_string(),
+            header: entity.header._ref.to_string(),
+            path: format!("{}/{}", entity.header._ref, entity.file_stem()),
+            deprecated: entity.deprecated.is_some(),
+        });
+
+        add_terms(&mut index, id, entity.name, NAME_WEIGHT);
+        add_terms(&mut index, id, entity.summary, SUMMARY_WEIGHT);
+
+        let parameters: Vec<&str> = match entity.kind {
+            "Function" => document
+                .functions
+                .iter()
+                .find(|f| f.name.as_str() == entity.name && f.header._ref == entity.header._ref)
+                .map(|f| f.parameters.iter().map(|p| p.name.as_str()).collect())
+                .unwrap_or_default(),
+            "Macro" => document
+                .macros
+                .iter()
+                .find(|m| m.name.as_str() == entity.name && m.header._ref == entity.header._ref)
+                .map(|m| match &m.kind {
+                    MacroKind::Function(f) => {
+                        f.parameters.iter().map(|p| p.name.as_str()).collect()
+                    }
+                    MacroKind::Object(_) => Vec::new(),
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        for parameter in parameters {
+            add_terms(&mut index, id, parameter, PARAMETER_WEIGHT);
+        }
+    }
+
+    let mut terms = Vec::with_capacity(index.len());
+    let mut postings = Vec::with_capacity(index.len());
+
+    for (term, documents) in index {
+        let mut ranked: Vec<(u32, u32)> = documents.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        terms.push(term);
+        postings.push(ranked.into_iter().flat_map(|(d, s)| [d, s]).collect());
+    }
+
+    SearchIndex {
+        weights: SearchWeights {
+            name: NAME_WEIGHT,
+            parameter: PARAMETER_WEIGHT,
+            summary: SUMMARY_WEIGHT,
+        },
+        documents,
+        terms,
+        postings,
+    }
+}
+
+fn add_terms(index: &mut BTreeMap<String, BTreeMap<u32, u32>>, id: u32, text: &str, weight: u32) {
+    for term in tokenize(text) {
+        *index.entry(term).or_default().entry(id).or_insert(0) += weight;
+    }
+}
+
+/// Lowercases and splits text into search terms. Identifiers are kept whole
+/// and also split on underscores so `pthread_create` matches `create`.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+
+    for word in text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+    {
+        let word = word.to_lowercase();
+        let trimmed = word.trim_matches('_');
+
+        if trimmed.is_empty() || STOP_WORDS.contains(&trimmed) {
+            continue;
+        }
+
+        terms.push(trimmed.to_string());
+
+        if trimmed.contains('_') {
+            for part in trimmed.split('_').filter(|p| p.len() > 1) {
+                terms.push(part.to_string());
+            }
+        }
+    }
+
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{document, function, function_macro, header};
+    use serde_json::json;
+
+    fn postings<'a>(index: &'a SearchIndex, term: &str) -> &'a [u32] {
+        let i = index.terms.binary_search(&term.to_string()).unwrap();
+        &index.postings[i]
+    }
+
+    #[test]
+    fn tokenize_lowercases_and_splits_identifiers() {
+        assert_eq!(
+            tokenize("Start __pthread_create() or A_B"),
+            vec!["start", "pthread_create", "pthread", "create", "a_b"]
+        );
+    }
+
+    #[test]
+    fn terms_are_sorted_and_postings_ranked_by_score() {
+        let mut create = function("pthread", "pthread_create", &["linux"]);
+        create["summary"] = json!("Create a thread.");
+        create["parameters"] = json!([
+            { "name": "thread", "type": "pthread_t *", "description": "" }
+        ]);
+        let mut join = function("pthread", "pthread_join", &["linux"]);
+        join["summary"] = json!("Wait for a thread.");
+
+        let index = build(&document(
+            vec![header("pthread", &["linux"])],
+            vec![create, join],
+            vec![function_macro("pthread", "THREAD_MAX", &["n"], &["linux"])],
+            vec![],
+            vec![],
+        ));
+
+        let mut sorted = index.terms.clone();
+        sorted.sort();
+        assert_eq!(index.terms, sorted);
+
+        assert_eq!(index.documents[0].path, "pthread/fn-pthread_create");
+        assert_eq!(index.documents[2].kind, "Macro");
+        // Names outrank parameters, which outrank summaries.
+        assert_eq!(postings(&index, "thread"), &[2, 12, 0, 5, 1, 2]);
+        assert_eq!(postings(&index, "create"), &[0, 12]);
+        assert!(index.terms.binary_search(&String::from("for")).is_err());
+    }
+}
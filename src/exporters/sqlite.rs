@@ -0,0 +1,1174 @@
+use super::{entities, find_entity, split_ref};
+use crate::{
+    definitions::{
+        _macro::MacroKind,
+        attributes::Attributes,
+        availability::Availability,
+        link::{Link, LinkLibraries},
+        standard::Standard,
+        typedef::TypedefRef,
+    },
+    Document,
+};
+use rusqlite::{params, Connection, Transaction};
+use std::{collections::HashMap, fs};
+
+const SCHEMA: &str = "
+CREATE TABLE headers (
+    id INTEGER PRIMARY KEY,
+    ref TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    summary TEXT NOT NULL
+);
+
+CREATE TABLE functions (
+    id INTEGER PRIMARY KEY,
+    header_id INTEGER NOT NULL REFERENCES headers(id),
+    name TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    return_type TEXT NOT NULL,
+    return_description TEXT NOT NULL,
+    cancellation_point INTEGER,
+    return_success TEXT,
+    return_failure TEXT,
+    sets_errno INTEGER,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE macros (
+    id INTEGER PRIMARY KEY,
+    header_id INTEGER NOT NULL REFERENCES headers(id),
+    name TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('object', 'function')),
+    return_type TEXT,
+    return_description TEXT,
+    cancellation_point INTEGER,
+    return_success TEXT,
+    return_failure TEXT,
+    sets_errno INTEGER,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE parameters (
+    id INTEGER PRIMARY KEY,
+    function_id INTEGER REFERENCES functions(id),
+    macro_id INTEGER REFERENCES macros(id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    type TEXT,
+    description TEXT NOT NULL,
+    CHECK ((function_id IS NULL) != (macro_id IS NULL))
+);
+
+CREATE TABLE examples (
+    id INTEGER PRIMARY KEY,
+    function_id INTEGER REFERENCES functions(id),
+    macro_id INTEGER REFERENCES macros(id),
+    title TEXT NOT NULL,
+    code TEXT NOT NULL,
+    CHECK ((function_id IS NULL) != (macro_id IS NULL))
+);
+
+CREATE TABLE errors (
+    entity_kind TEXT NOT NULL CHECK (entity_kind IN ('function', 'macro')),
+    entity_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    errno TEXT NOT NULL,
+    condition TEXT NOT NULL,
+    macro_id INTEGER REFERENCES macros(id)
+);
+
+CREATE TABLE associated (
+    function_id INTEGER REFERENCES functions(id),
+    macro_id INTEGER REFERENCES macros(id),
+    reference TEXT NOT NULL,
+    target_kind TEXT,
+    target_id INTEGER,
+    CHECK ((function_id IS NULL) != (macro_id IS NULL))
+);
+
+CREATE TABLE enums (
+    id INTEGER PRIMARY KEY,
+    header_id INTEGER NOT NULL REFERENCES headers(id),
+    name TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE variants (
+    id INTEGER PRIMARY KEY,
+    enum_id INTEGER NOT NULL REFERENCES enums(id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE structs (
+    id INTEGER PRIMARY KEY,
+    header_id INTEGER NOT NULL REFERENCES headers(id),
+    name TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE fields (
+    id INTEGER PRIMARY KEY,
+    struct_id INTEGER NOT NULL REFERENCES structs(id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    type TEXT NOT NULL,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE typedefs (
+    id INTEGER PRIMARY KEY,
+    header_id INTEGER NOT NULL REFERENCES headers(id),
+    name TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    type TEXT NOT NULL,
+    description TEXT NOT NULL,
+    associated_enum_id INTEGER REFERENCES enums(id),
+    associated_struct_id INTEGER REFERENCES structs(id)
+);
+
+CREATE TABLE deprecated (
+    entity_kind TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    since TEXT,
+    removed TEXT,
+    reason TEXT NOT NULL,
+    replacement TEXT,
+    replacement_kind TEXT,
+    replacement_id INTEGER,
+    PRIMARY KEY (entity_kind, entity_id)
+);
+
+CREATE TABLE os_affinity (
+    entity_kind TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    os TEXT NOT NULL,
+    PRIMARY KEY (entity_kind, entity_id, os)
+);
+
+CREATE TABLE arch_affinity (
+    entity_kind TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    arch TEXT NOT NULL,
+    PRIMARY KEY (entity_kind, entity_id, arch)
+);
+
+CREATE TABLE standards (
+    entity_kind TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    version TEXT NOT NULL,
+    option TEXT
+);
+
+CREATE TABLE availability (
+    entity_kind TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    platform TEXT NOT NULL,
+    introduced TEXT NOT NULL,
+    removed TEXT,
+    PRIMARY KEY (entity_kind, entity_id, platform)
+);
+
+CREATE TABLE feature_test_macros (
+    entity_kind TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT,
+    macro_id INTEGER REFERENCES macros(id)
+);
+
+-- One row for the default (`os` NULL) and one per OS override.
+CREATE TABLE links (
+    id INTEGER PRIMARY KEY,
+    entity_kind TEXT NOT NULL CHECK (entity_kind IN ('header', 'function')),
+    entity_id INTEGER NOT NULL,
+    os TEXT
+);
+
+CREATE TABLE link_arguments (
+    link_id INTEGER NOT NULL REFERENCES links(id),
+    position INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('library', 'pkg_config', 'flag')),
+    value TEXT NOT NULL
+);
+
+CREATE TABLE safety (
+    entity_kind TEXT NOT NULL CHECK (entity_kind IN ('function', 'macro')),
+    entity_id INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('thread', 'async_signal', 'async_cancel')),
+    level TEXT NOT NULL,
+    PRIMARY KEY (entity_kind, entity_id, kind)
+);
+
+CREATE TABLE safety_remarks (
+    entity_kind TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    remark TEXT NOT NULL
+);
+
+CREATE VIRTUAL TABLE search USING fts5(
+    entity_kind UNINDEXED,
+    entity_id UNINDEXED,
+    name,
+    summary,
+    description
+);
+";
+
+/// Writes the document to a normalised SQLite database at `output_path`,
+/// replacing any existing file.
+pub(crate) fn export(document: &Document, output_path: &str) -> rusqlite::Result<()> {
+    let _ = fs::remove_file(output_path);
+
+    let mut connection = Connection::open(output_path)?;
+    connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+    connection.execute_batch(SCHEMA)?;
+
+    let transaction = connection.transaction()?;
+    insert_document(&transaction, document)?;
+    transaction.commit()
+}
+
+fn insert_document(tx: &Transaction, document: &Document) -> rusqlite::Result<()> {
+    let mut header_ids = HashMap::new();
+    // (kind, header ref, name) -> row id, used to resolve cross references.
+    let mut entity_ids: HashMap<(&str, &str, &str), i64> = HashMap::new();
+    let mut macro_ids = Vec::new();
+
+    for header in document.headers.iter() {
+        tx.execute(
+            "INSERT INTO headers (ref, name, summary) VALUES (?1, ?2, ?3)",
+            params![&*header._ref, &*header.name, &*header.summary],
+        )?;
+        let id = tx.last_insert_rowid();
+        header_ids.insert(header._ref.as_str(), id);
+        insert_os_affinity(tx, "header", id, &header.os_affinity)?;
+        insert_arch_affinity(tx, "header", id, &header.arch_affinity)?;
+        insert_standards(tx, "header", id, &header.standards)?;
+        insert_link(tx, "header", id, header.link.as_ref())?;
+    }
+
+    for _enum in document.enums.iter() {
+        tx.execute(
+            "INSERT INTO enums (header_id, name, summary, description) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                header_ids.get(_enum.header._ref.as_str()),
+                &*_enum.name,
+                &*_enum.summary,
+                &*_enum.description
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        entity_ids.insert(("enum", &_enum.header._ref, &_enum.name), id);
+        insert_os_affinity(tx, "enum", id, &_enum.os_affinity)?;
+        insert_arch_affinity(tx, "enum", id, &_enum.arch_affinity)?;
+        insert_standards(tx, "enum", id, &_enum.standards)?;
+        insert_availability(tx, "enum", id, &_enum.availability)?;
+        insert_search(
+            tx,
+            "enum",
+            id,
+            &_enum.name,
+            &_enum.summary,
+            &_enum.description,
+        )?;
+
+        for (position, variant) in _enum.variants.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO variants (enum_id, position, name, description) VALUES (?1, ?2, ?3, ?4)",
+                params![id, position as i64, &*variant.name, &*variant.description],
+            )?;
+        }
+    }
+
+    for _struct in document.structs.iter() {
+        tx.execute(
+            "INSERT INTO structs (header_id, name, summary, description) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                header_ids.get(_struct.header._ref.as_str()),
+                &*_struct.name,
+                &*_struct.summary,
+                &*_struct.description
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        entity_ids.insert(("struct", &_struct.header._ref, &_struct.name), id);
+        insert_os_affinity(tx, "struct", id, &_struct.os_affinity)?;
+        insert_arch_affinity(tx, "struct", id, &_struct.arch_affinity)?;
+        insert_standards(tx, "struct", id, &_struct.standards)?;
+        insert_availability(tx, "struct", id, &_struct.availability)?;
+        insert_search(
+            tx,
+            "struct",
+            id,
+            &_struct.name,
+            &_struct.summary,
+            &_struct.description,
+        )?;
+
+        for (position, field) in _struct.fields.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO fields (struct_id, position, name, type, description) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, position as i64, &*field.name, &*field._type, &*field.description],
+            )?;
+        }
+    }
+
+    for typedef in document.typedefs.iter() {
+        let (enum_id, struct_id) = match &typedef.associated_ref {
+            TypedefRef::None(_) => (None, None),
+            TypedefRef::Enumeration(e) => (
+                entity_ids
+                    .get(&("enum", e.header._ref.as_str(), e.name.as_str()))
+                    .copied(),
+                None,
+            ),
+            TypedefRef::Struct(s) => (
+                None,
+                entity_ids
+                    .get(&("struct", s.header._ref.as_str(), s.name.as_str()))
+                    .copied(),
+            ),
+        };
+
+        tx.execute(
+            "INSERT INTO typedefs (header_id, name, summary, type, description, associated_enum_id, associated_struct_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                header_ids.get(typedef.header._ref.as_str()),
+                &*typedef.name,
+                &*typedef.summary,
+                &*typedef._type,
+                &*typedef.description,
+                enum_id,
+                struct_id
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        entity_ids.insert(("typedef", &typedef.header._ref, &typedef.name), id);
+        insert_os_affinity(tx, "typedef", id, &typedef.os_affinity)?;
+        insert_arch_affinity(tx, "typedef", id, &typedef.arch_affinity)?;
+        insert_standards(tx, "typedef", id, &typedef.standards)?;
+        insert_availability(tx, "typedef", id, &typedef.availability)?;
+        insert_search(
+            tx,
+            "typedef",
+            id,
+            &typedef.name,
+            &typedef.summary,
+            &typedef.description,
+        )?;
+    }
+
+    for _macro in document.macros.iter() {
+        let (kind, returns, attributes) = match &_macro.kind {
+            MacroKind::Object(_) => ("object", None, None),
+            MacroKind::Function(function) => (
+                "function",
+                Some(&function.returns),
+                function.attributes.as_deref(),
+            ),
+        };
+
+        let convention = returns.and_then(|r| r.convention.as_ref());
+
+        tx.execute(
+            "INSERT INTO macros (header_id, name, summary, kind, return_type, return_description, cancellation_point, return_success, return_failure, sets_errno, description) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                header_ids.get(_macro.header._ref.as_str()),
+                &*_macro.name,
+                &*_macro.summary,
+                kind,
+                returns.map(|r| r._type.as_str()),
+                returns.map(|r| r.description.as_str()),
+                attributes.and_then(|a| a.cancellation_point),
+                convention.and_then(|c| c.success.as_deref()),
+                convention.and_then(|c| c.failure.as_deref()),
+                convention.map(|c| c.sets_errno),
+                &*_macro.description
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        macro_ids.push(id);
+        entity_ids.insert(("macro", &_macro.header._ref, &_macro.name), id);
+        insert_os_affinity(tx, "macro", id, &_macro.os_affinity)?;
+        insert_arch_affinity(tx, "macro", id, &_macro.arch_affinity)?;
+        insert_standards(tx, "macro", id, &_macro.standards)?;
+        insert_availability(tx, "macro", id, &_macro.availability)?;
+        insert_safety(tx, "macro", id, attributes)?;
+        insert_search(
+            tx,
+            "macro",
+            id,
+            &_macro.name,
+            &_macro.summary,
+            &_macro.description,
+        )?;
+
+        if let MacroKind::Function(function) = &_macro.kind {
+            for (position, parameter) in function.parameters.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO parameters (macro_id, position, name, description) VALUES (?1, ?2, ?3, ?4)",
+                    params![id, position as i64, &*parameter.name, &*parameter.description],
+                )?;
+            }
+
+            for example in function.examples.iter() {
+                tx.execute(
+                    "INSERT INTO examples (macro_id, title, code) VALUES (?1, ?2, ?3)",
+                    params![id, &*example.title, &*example.code],
+                )?;
+            }
+        }
+    }
+
+    let mut function_ids = Vec::new();
+
+    for function in document.functions.iter() {
+        let convention = function.returns.convention.as_ref();
+
+        tx.execute(
+            "INSERT INTO functions (header_id, name, summary, return_type, return_description, cancellation_point, return_success, return_failure, sets_errno, description) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                header_ids.get(function.header._ref.as_str()),
+                &*function.name,
+                &*function.summary,
+                &*function.returns._type,
+                &*function.returns.description,
+                function
+                    .attributes
+                    .as_ref()
+                    .and_then(|a| a.cancellation_point),
+                convention.and_then(|c| c.success.as_deref()),
+                convention.and_then(|c| c.failure.as_deref()),
+                convention.map(|c| c.sets_errno),
+                &*function.description
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        function_ids.push(id);
+        entity_ids.insert(("function", &function.header._ref, &function.name), id);
+        insert_os_affinity(tx, "function", id, &function.os_affinity)?;
+        insert_arch_affinity(tx, "function", id, &function.arch_affinity)?;
+        insert_standards(tx, "function", id, &function.standards)?;
+        insert_link(tx, "function", id, function.link.as_ref())?;
+        insert_availability(tx, "function", id, &function.availability)?;
+        insert_safety(tx, "function", id, function.attributes.as_ref())?;
+        insert_search(
+            tx,
+            "function",
+            id,
+            &function.name,
+            &function.summary,
+            &function.description,
+        )?;
+
+        for (position, parameter) in function.parameters.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO parameters (function_id, position, name, type, description) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, position as i64, &*parameter.name, &*parameter._type, &*parameter.description],
+            )?;
+        }
+
+        for example in function.examples.iter() {
+            tx.execute(
+                "INSERT INTO examples (function_id, title, code) VALUES (?1, ?2, ?3)",
+                params![id, &*example.title, &*example.code],
+            )?;
+        }
+    }
+
+    // Associated references can point at any entity, so they are resolved
+    // once every table has been populated.
+    let owners = document
+        .functions
+        .iter()
+        .zip(function_ids)
+        .map(|(function, id)| ("function_id", id, &function.associated))
+        .chain(
+            document
+                .macros
+                .iter()
+                .zip(macro_ids)
+                .map(|(_macro, id)| ("macro_id", id, &_macro.associated)),
+        );
+
+    for (owner_column, id, associated) in owners {
+        for reference in associated.iter() {
+            let target = find_entity(document, reference).and_then(|entity| {
+                let kind = table_kind(entity.kind);
+                entity_ids
+                    .get(&(kind, entity.header._ref.as_str(), entity.name))
+                    .map(|target_id| (kind, *target_id))
+            });
+
+            tx.execute(
+                &format!(
+                    "INSERT INTO associated ({}, reference, target_kind, target_id) VALUES (?1, ?2, ?3, ?4)",
+                    owner_column
+                ),
+                params![id, reference, target.map(|t| t.0), target.map(|t| t.1)],
+            )?;
+        }
+    }
+
+    // So are deprecation replacements, which any entity kind can have.
+    for entity in entities(document) {
+        let deprecated = match entity.deprecated {
+            Some(deprecated) => deprecated,
+            None => continue,
+        };
+        let kind = table_kind(entity.kind);
+        let id = entity_ids.get(&(kind, entity.header._ref.as_str(), entity.name));
+        let target = deprecated
+            .replacement
+            .as_ref()
+            .and_then(|reference| find_entity(document, reference))
+            .and_then(|target| {
+                let kind = table_kind(target.kind);
+                entity_ids
+                    .get(&(kind, target.header._ref.as_str(), target.name))
+                    .map(|target_id| (kind, *target_id))
+            });
+
+        tx.execute(
+            "INSERT INTO deprecated (entity_kind, entity_id, since, removed, reason, replacement, replacement_kind, replacement_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                kind,
+                id,
+                deprecated.since,
+                deprecated.removed,
+                deprecated.reason,
+                deprecated.replacement,
+                target.map(|t| t.0),
+                target.map(|t| t.1)
+            ],
+        )?;
+    }
+
+    // Feature test macros link to the macro documenting them, so they wait
+    // for the macros table too.
+    let requirements = document
+        .functions
+        .iter()
+        .map(|f| ("function", &f.header, &f.name, &f.feature_test_macros))
+        .chain(
+            document
+                .macros
+                .iter()
+                .map(|m| ("macro", &m.header, &m.name, &m.feature_test_macros)),
+        )
+        .chain(
+            document
+                .structs
+                .iter()
+                .map(|s| ("struct", &s.header, &s.name, &s.feature_test_macros)),
+        )
+        .chain(
+            document
+                .typedefs
+                .iter()
+                .map(|t| ("typedef", &t.header, &t.name, &t.feature_test_macros)),
+        );
+
+    for (kind, header, name, feature_test_macros) in requirements {
+        let id = entity_ids.get(&(kind, header._ref.as_str(), name.as_str()));
+
+        for (position, feature_test_macro) in feature_test_macros.iter().enumerate() {
+            let macro_id = feature_test_macro
+                ._ref
+                .as_deref()
+                .and_then(split_ref)
+                .and_then(|(header_ref, name)| entity_ids.get(&("macro", header_ref, name)));
+
+            tx.execute(
+                "INSERT INTO feature_test_macros (entity_kind, entity_id, position, name, value, macro_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    kind,
+                    id,
+                    position as i64,
+                    feature_test_macro.name,
+                    feature_test_macro.value,
+                    macro_id
+                ],
+            )?;
+        }
+    }
+
+    // As do errno values, which function-like macros can fail with too.
+    let failures = document
+        .functions
+        .iter()
+        .map(|f| ("function", &f.header, &f.name, &f.errors))
+        .chain(document.macros.iter().filter_map(|m| match &m.kind {
+            MacroKind::Function(function) => Some(("macro", &m.header, &m.name, &function.errors)),
+            MacroKind::Object(_) => None,
+        }));
+
+    for (kind, header, name, errors) in failures {
+        let id = entity_ids.get(&(kind, header._ref.as_str(), name.as_str()));
+
+        for (position, error) in errors.iter().enumerate() {
+            let macro_id = error
+                ._ref
+                .as_deref()
+                .and_then(split_ref)
+                .and_then(|(header_ref, name)| entity_ids.get(&("macro", header_ref, name)));
+
+            tx.execute(
+                "INSERT INTO errors (entity_kind, entity_id, position, errno, condition, macro_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![kind, id, position as i64, &error.errno, &error.condition, macro_id],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Maps an exporter entity kind onto the `entity_kind` values used in the
+/// database.
+fn table_kind(kind: &str) -> &'static str {
+    match kind {
+        "Function" => "function",
+        "Macro" => "macro",
+        "Enum" => "enum",
+        "Struct" => "struct",
+        _ => "typedef",
+    }
+}
+
+fn insert_os_affinity(
+    tx: &Transaction,
+    entity_kind: &str,
+    entity_id: i64,
+    os_affinity: &[String],
+) -> rusqlite::Result<()> {
+    for os in os_affinity.iter() {
+        tx.execute(
+            "INSERT OR IGNORE INTO os_affinity (entity_kind, entity_id, os) VALUES (?1, ?2, ?3)",
+            params![entity_kind, entity_id, os],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Architecture independent entities (an empty `arch_affinity`) get no
+/// rows.
+fn insert_arch_affinity(
+    tx: &Transaction,
+    entity_kind: &str,
+    entity_id: i64,
+    arch_affinity: &[String],
+) -> rusqlite::Result<()> {
+    for arch in arch_affinity.iter() {
+        tx.execute(
+            "INSERT OR IGNORE INTO arch_affinity (entity_kind, entity_id, arch) VALUES (?1, ?2, ?3)",
+            params![entity_kind, entity_id, arch],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn insert_standards(
+    tx: &Transaction,
+    entity_kind: &str,
+    entity_id: i64,
+    standards: &[Standard],
+) -> rusqlite::Result<()> {
+    for standard in standards.iter() {
+        tx.execute(
+            "INSERT INTO standards (entity_kind, entity_id, name, version, option) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![entity_kind, entity_id, standard.name, standard.version, standard.option],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn insert_availability(
+    tx: &Transaction,
+    entity_kind: &str,
+    entity_id: i64,
+    availability: &[Availability],
+) -> rusqlite::Result<()> {
+    for entry in availability.iter() {
+        tx.execute(
+            "INSERT INTO availability (entity_kind, entity_id, platform, introduced, removed) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![entity_kind, entity_id, entry.platform, entry.introduced, entry.removed],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn insert_link(
+    tx: &Transaction,
+    entity_kind: &str,
+    entity_id: i64,
+    link: Option<&Link>,
+) -> rusqlite::Result<()> {
+    let link = match link {
+        Some(link) => link,
+        None => return Ok(()),
+    };
+
+    let mut entries: Vec<(Option<&str>, &LinkLibraries)> = vec![(None, &link.libraries)];
+    for os_link in link.os_overrides.iter() {
+        for os in os_link.os.iter() {
+            entries.push((Some(os), &os_link.libraries));
+        }
+    }
+
+    for (os, libraries) in entries {
+        tx.execute(
+            "INSERT INTO links (entity_kind, entity_id, os) VALUES (?1, ?2, ?3)",
+            params![entity_kind, entity_id, os],
+        )?;
+        let link_id = tx.last_insert_rowid();
+
+        let arguments = libraries
+            .libraries
+            .iter()
+            .map(|l| ("library", l))
+            .chain(libraries.pkg_config.iter().map(|p| ("pkg_config", p)))
+            .chain(libraries.flags.iter().map(|f| ("flag", f)));
+
+        for (position, (kind, value)) in arguments.enumerate() {
+            tx.execute(
+                "INSERT INTO link_arguments (link_id, position, kind, value) VALUES (?1, ?2, ?3, ?4)",
+                params![link_id, position as i64, kind, value],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn insert_safety(
+    tx: &Transaction,
+    entity_kind: &str,
+    entity_id: i64,
+    attributes: Option<&Attributes>,
+) -> rusqlite::Result<()> {
+    let attributes = match attributes {
+        Some(attributes) => attributes,
+        None => return Ok(()),
+    };
+
+    let safeties = [
+        ("thread", &attributes.thread_safety),
+        ("async_signal", &attributes.async_signal_safety),
+        ("async_cancel", &attributes.async_cancel_safety),
+    ];
+
+    for (kind, safety) in safeties {
+        let safety = match safety {
+            Some(safety) => safety,
+            None => continue,
+        };
+
+        tx.execute(
+            "INSERT INTO safety (entity_kind, entity_id, kind, level) VALUES (?1, ?2, ?3, ?4)",
+            params![entity_kind, entity_id, kind, safety.level],
+        )?;
+
+        for (position, remark) in safety.reasons.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO safety_remarks (entity_kind, entity_id, kind, position, remark) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![entity_kind, entity_id, kind, position as i64, remark],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn insert_search(
+    tx: &Transaction,
+    entity_kind: &str,
+    entity_id: i64,
+    name: &str,
+    summary: &str,
+    description: &str,
+) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT INTO search (entity_kind, entity_id, name, summary, description) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![entity_kind, entity_id, name, summary, description],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{
+        _struct, document, function, function_macro, header, object_macro, temp_dir, typedef,
+    };
+    use serde_json::json;
+
+    fn database() -> Connection {
+        let mut puts = function("stdio", "puts", &["linux", "windows"]);
+        puts["parameters"] =
+            json!([{ "name": "s", "type": "const char *", "description": "The line." }]);
+        puts["associated"] = json!(["stdio/getc", "stdio/missing"]);
+        puts["arch_affinity"] = json!(["x86_64", "aarch64"]);
+        puts["availability"] = json!([
+            { "platform": "glibc", "introduced": "2.0" },
+            { "platform": "musl", "introduced": "1.0", "removed": "1.2" },
+        ]);
+        puts["link"] = json!({
+            "libraries": ["m"],
+            "flags": ["-pthread"],
+            "os_overrides": [{ "os": ["macos", "windows"] }],
+        });
+        puts["attributes"] = json!({
+            "thread_safety": { "level": "MT-Safe", "reasons": ["locale", "race:stdout"] },
+            "cancellation_point": true,
+        });
+        puts["returns"]["convention"] =
+            json!({ "success": "0", "failure": "EOF", "sets_errno": true });
+        puts["errors"] = json!([
+            { "errno": "EIO", "condition": "A write failed.", "ref": "stdio/EIO" },
+            { "errno": "EOVERFLOW", "condition": "The line is too long." },
+        ]);
+        puts["standards"] = json!([
+            { "name": "C", "version": "C89" },
+            { "name": "POSIX", "version": "POSIX.1-2008", "option": "XSI" },
+        ]);
+
+        let mut getc = function_macro("stdio", "getc", &["fp"], &["linux"]);
+        getc["associated"] = json!(["stdio/puts"]);
+        getc["kind"]["function"]["attributes"] =
+            json!({ "async_signal_safety": { "level": "AS-Unsafe", "reasons": ["lock"] } });
+        getc["kind"]["function"]["errors"] =
+            json!([{ "errno": "EIO", "condition": "A read failed.", "ref": "stdio/EIO" }]);
+        getc["deprecated"] =
+            json!({ "since": "C23", "reason": "Slow.", "replacement": "stdio/puts" });
+
+        let mut fpos_t = typedef("stdio", "fpos_t", "struct fpos", &["linux"]);
+        let mut fpos = _struct("stdio", "fpos", &["linux"]);
+        fpos["fields"] = json!([{ "name": "pos", "type": "long", "description": "" }]);
+        fpos_t["associated_ref"] = json!({ "struct": fpos.clone() });
+        fpos_t["feature_test_macros"] = json!([
+            { "name": "_GNU_SOURCE", "ref": "stdio/_GNU_SOURCE" },
+            { "name": "_POSIX_C_SOURCE", "value": "200809L" },
+        ]);
+
+        let document = document(
+            vec![header("stdio", &["linux", "windows"])],
+            vec![puts],
+            vec![
+                getc,
+                object_macro("stdio", "EOF", &["linux"]),
+                object_macro("stdio", "_GNU_SOURCE", &[]),
+                object_macro("stdio", "EIO", &[]),
+            ],
+            vec![fpos],
+            vec![fpos_t],
+        );
+
+        let root = temp_dir("sqlite");
+        let path = root.join("capibara.db");
+        export(&document, path.to_str().unwrap()).unwrap();
+        let connection = Connection::open(&path).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        connection
+    }
+
+    fn rows(connection: &Connection, sql: &str) -> Vec<String> {
+        let mut statement = connection.prepare(sql).unwrap();
+        let rows = statement.query_map([], |row| row.get(0)).unwrap();
+        rows.map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn schema_has_a_table_per_entity_kind() {
+        let connection = database();
+
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'search_%' ORDER BY name"
+            ),
+            vec![
+                "arch_affinity",
+                "associated",
+                "availability",
+                "deprecated",
+                "enums",
+                "errors",
+                "examples",
+                "feature_test_macros",
+                "fields",
+                "functions",
+                "headers",
+                "link_arguments",
+                "links",
+                "macros",
+                "os_affinity",
+                "parameters",
+                "safety",
+                "safety_remarks",
+                "search",
+                "standards",
+                "structs",
+                "typedefs",
+                "variants"
+            ]
+        );
+    }
+
+    #[test]
+    fn entities_reference_their_header_and_children() {
+        let connection = database();
+
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT h.name || ' ' || f.name || ' ' || p.position || ' ' || p.type || ' ' || p.name
+                 FROM functions f JOIN headers h ON h.id = f.header_id
+                 JOIN parameters p ON p.function_id = f.id"
+            ),
+            vec!["stdio.h puts 0 const char * s"]
+        );
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT m.name || ' ' || m.kind || ' ' || p.name
+                 FROM macros m JOIN parameters p ON p.macro_id = m.id"
+            ),
+            vec!["getc function fp"]
+        );
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT t.name || ' ' || s.name FROM typedefs t
+                 JOIN structs s ON s.id = t.associated_struct_id"
+            ),
+            vec!["fpos_t fpos"]
+        );
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT entity_kind || ' ' || os FROM os_affinity ORDER BY entity_kind, os"
+            ),
+            vec![
+                "function linux",
+                "function windows",
+                "header linux",
+                "header windows",
+                "macro linux",
+                "macro linux",
+                "struct linux",
+                "typedef linux"
+            ]
+        );
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT entity_kind || ' ' || arch FROM arch_affinity ORDER BY entity_kind, arch"
+            ),
+            vec!["function aarch64", "function x86_64"]
+        );
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT entity_kind || ' ' || name || ' ' || version || coalesce('+' || option, '')
+                 FROM standards ORDER BY rowid"
+            ),
+            vec!["function C C89", "function POSIX POSIX.1-2008+XSI"]
+        );
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT entity_kind || ' ' || platform || ' ' || introduced || coalesce('-' || removed, '')
+                 FROM availability ORDER BY platform"
+            ),
+            vec!["function glibc 2.0", "function musl 1.0-1.2"]
+        );
+    }
+
+    #[test]
+    fn feature_test_macros_resolve_to_macro_rows() {
+        let connection = database();
+
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT t.name || ' ' || r.position || ' ' || r.name || coalesce(' ' || r.value, '') || ' ' ||
+                        coalesce('macro:' || m.name, 'unresolved')
+                 FROM feature_test_macros r
+                 JOIN typedefs t ON r.entity_kind = 'typedef' AND t.id = r.entity_id
+                 LEFT JOIN macros m ON m.id = r.macro_id
+                 ORDER BY r.position"
+            ),
+            vec![
+                "fpos_t 0 _GNU_SOURCE macro:_GNU_SOURCE",
+                "fpos_t 1 _POSIX_C_SOURCE 200809L unresolved"
+            ]
+        );
+    }
+
+    #[test]
+    fn safety_attributes_have_a_row_per_level_and_remark() {
+        let connection = database();
+
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT s.entity_kind || ' ' || s.kind || ' ' || s.level ||
+                        coalesce(' ' || r.position || ':' || r.remark, '')
+                 FROM safety s LEFT JOIN safety_remarks r
+                   ON r.entity_kind = s.entity_kind AND r.entity_id = s.entity_id AND r.kind = s.kind
+                 ORDER BY s.entity_kind, r.position"
+            ),
+            vec![
+                "function thread MT-Safe 0:locale",
+                "function thread MT-Safe 1:race:stdout",
+                "macro async_signal AS-Unsafe 0:lock"
+            ]
+        );
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT name || ' ' || coalesce(cancellation_point, 'unknown') FROM functions
+                 UNION ALL
+                 SELECT name || ' ' || coalesce(cancellation_point, 'unknown') FROM macros WHERE kind = 'function'"
+            ),
+            vec!["puts 1", "getc unknown"]
+        );
+    }
+
+    #[test]
+    fn errors_resolve_to_errno_macro_rows() {
+        let connection = database();
+
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT e.entity_kind || ' ' || e.position || ' ' || e.errno || ' ' ||
+                        coalesce('macro:' || m.name, 'unresolved')
+                 FROM errors e LEFT JOIN macros m ON m.id = e.macro_id
+                 ORDER BY e.entity_kind, e.position"
+            ),
+            vec![
+                "function 0 EIO macro:EIO",
+                "function 1 EOVERFLOW unresolved",
+                "macro 0 EIO macro:EIO"
+            ]
+        );
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT name || ' ' || coalesce(return_success, '-') || ' ' ||
+                        coalesce(return_failure, '-') || ' ' || coalesce(sets_errno, '-')
+                 FROM functions
+                 UNION ALL
+                 SELECT name || ' ' || coalesce(return_success, '-') || ' ' ||
+                        coalesce(return_failure, '-') || ' ' || coalesce(sets_errno, '-')
+                 FROM macros WHERE kind = 'function'"
+            ),
+            vec!["puts 0 EOF 1", "getc - - -"]
+        );
+    }
+
+    #[test]
+    fn links_have_a_row_per_os_override() {
+        let connection = database();
+
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT l.entity_kind || ' ' || coalesce(l.os, 'default') ||
+                        coalesce(' ' || a.position || ' ' || a.kind || '=' || a.value, '')
+                 FROM links l LEFT JOIN link_arguments a ON a.link_id = l.id
+                 ORDER BY l.id, a.position"
+            ),
+            vec![
+                "function default 0 library=m",
+                "function default 1 flag=-pthread",
+                "function macos",
+                "function windows"
+            ]
+        );
+    }
+
+    #[test]
+    fn associated_references_resolve_to_rows() {
+        let connection = database();
+
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT coalesce(f.name, m.name) || ' -> ' || a.reference || ' ' ||
+                        coalesce(a.target_kind || ':' || coalesce(tf.name, tm.name), 'unresolved')
+                 FROM associated a
+                 LEFT JOIN functions f ON f.id = a.function_id
+                 LEFT JOIN macros m ON m.id = a.macro_id
+                 LEFT JOIN functions tf ON a.target_kind = 'function' AND tf.id = a.target_id
+                 LEFT JOIN macros tm ON a.target_kind = 'macro' AND tm.id = a.target_id
+                 ORDER BY a.rowid"
+            ),
+            vec![
+                "puts -> stdio/getc macro:getc",
+                "puts -> stdio/missing unresolved",
+                "getc -> stdio/puts function:puts"
+            ]
+        );
+    }
+
+    #[test]
+    fn deprecation_replacements_resolve_to_rows() {
+        let connection = database();
+
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT m.name || ' ' || d.since || ' ' || d.reason || ' -> ' || f.name
+                 FROM deprecated d
+                 JOIN macros m ON d.entity_kind = 'macro' AND m.id = d.entity_id
+                 JOIN functions f ON d.replacement_kind = 'function' AND f.id = d.replacement_id"
+            ),
+            vec!["getc C23 Slow. -> puts"]
+        );
+    }
+
+    #[test]
+    fn search_matches_names_and_descriptions() {
+        let connection = database();
+
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT entity_kind || ' ' || name FROM search WHERE search MATCH 'getc'"
+            ),
+            vec!["macro getc"]
+        );
+        assert_eq!(
+            rows(
+                &connection,
+                "SELECT name FROM search WHERE search MATCH 'description' ORDER BY name"
+            ),
+            vec![
+                "EIO",
+                "EOF",
+                "_GNU_SOURCE",
+                "fpos",
+                "fpos_t",
+                "getc",
+                "puts"
+            ]
+        );
+    }
+}
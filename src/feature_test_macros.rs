@@ -0,0 +1,88 @@
+//! The `feature_test_macros` an entity needs defined before its header is
+//! included, linked to the macro documenting each one when the corpus has
+//! one.
+//!
+//! ```yaml
+//! feature_test_macros:
+//!   - name: _POSIX_C_SOURCE
+//!     value: 200809L
+//!   - name: _DEFAULT_SOURCE
+//! ```
+//!
+//! The first entry is the one synopses define; the rest are alternatives.
+
+use crate::definitions::{_macro::Macro, feature_test_macro::FeatureTestMacro};
+
+/// Points every feature test macro at the macro documenting it. Names no
+/// macro documents are kept with no reference.
+pub(crate) fn resolve(
+    feature_test_macros: Vec<FeatureTestMacro>,
+    macros: &[Macro],
+) -> Vec<FeatureTestMacro> {
+    feature_test_macros
+        .into_iter()
+        .map(|feature_test_macro| {
+            let name = feature_test_macro.name.trim();
+            let _ref = macros
+                .iter()
+                .find(|m| m.name.as_str() == name)
+                .map(|m| format!("{}/{}", m.header._ref, m.name));
+
+            FeatureTestMacro {
+                name: name.to_string(),
+                value: feature_test_macro.value,
+                _ref,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::object_macro;
+
+    fn requirement(name: &str) -> FeatureTestMacro {
+        FeatureTestMacro {
+            name: name.to_string(),
+            value: None,
+            _ref: None,
+        }
+    }
+
+    #[test]
+    fn resolve_links_names_to_documented_macros() {
+        let macros: Vec<Macro> = vec![
+            serde_json::from_value(object_macro("features", "_GNU_SOURCE", &[])).unwrap(),
+            serde_json::from_value(object_macro("features", "_POSIX_C_SOURCE", &[])).unwrap(),
+        ];
+        let mut posix = requirement(" _POSIX_C_SOURCE ");
+        posix.value = Some(String::from("200809L"));
+
+        let resolved = resolve(
+            vec![
+                posix,
+                requirement("_DEFAULT_SOURCE"),
+                requirement("_GNU_SOURCE"),
+            ],
+            &macros,
+        );
+
+        let resolved: Vec<(&str, Option<&str>, Option<&str>)> = resolved
+            .iter()
+            .map(|f| (f.name.as_str(), f.value.as_deref(), f._ref.as_deref()))
+            .collect();
+        assert_eq!(
+            resolved,
+            vec![
+                (
+                    "_POSIX_C_SOURCE",
+                    Some("200809L"),
+                    Some("features/_POSIX_C_SOURCE")
+                ),
+                ("_DEFAULT_SOURCE", None, None),
+                ("_GNU_SOURCE", None, Some("features/_GNU_SOURCE")),
+            ]
+        );
+    }
+}
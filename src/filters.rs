@@ -0,0 +1,431 @@
+//! Build filters that narrow a discovered `Document` to a product's
+//! platforms and standards. Each filter removes entities, then `prune` drops
+//! what the removal leaves behind: empty headers, and `associated`
+//! references, deprecation replacements, errno macros and typedef targets
+//! pointing at removed entities.
+
+use crate::{
+    availability::AvailabilityFilter,
+    definitions::{
+        _macro::MacroKind,
+        header::HeaderSummary,
+        typedef::{self, TypedefRef},
+    },
+    exporters::{entities, find_entity, EntityRef},
+    standards::StandardFilter,
+    Document,
+};
+
+/// Keeps the entities whose `os_affinity` includes at least one of `os`
+/// (canonical names), narrows each remaining header's `os_affinity` to `os`
+/// and records the filter on the document.
+pub(crate) fn by_os(document: &mut Document, os: &[String]) {
+    retain(document, |entity| {
+        entity.os_affinity.iter().any(|a| os.contains(a))
+    });
+    for header in document.headers.iter_mut() {
+        header.os_affinity.retain(|a| os.contains(a));
+    }
+    document.os_filter = os.to_vec();
+}
+
+/// Keeps architecture independent entities (empty `arch_affinity`) and
+/// those that support at least one of `arch`, narrows the `arch_affinity`
+/// of each remaining architecture specific header to `arch` and records the
+/// filter on the document.
+pub(crate) fn by_arch(document: &mut Document, arch: &[String]) {
+    retain(document, |entity| {
+        entity.arch_affinity.is_empty() || entity.arch_affinity.iter().any(|a| arch.contains(a))
+    });
+    for header in document.headers.iter_mut() {
+        header.arch_affinity.retain(|a| arch.contains(a));
+    }
+    document.arch_filter = arch.to_vec();
+}
+
+/// Keeps the entities that belong to at least one of `standards` and records
+/// the filter on the document.
+pub(crate) fn by_standard(document: &mut Document, standards: &[StandardFilter]) {
+    retain(document, |entity| {
+        standards
+            .iter()
+            .any(|standard| standard.matches(entity.standards))
+    });
+    document.standard_filter = standards.iter().map(|s| s.to_string()).collect();
+}
+
+/// Keeps the entities that every `available` filter lets through and
+/// records the filters on the document.
+pub(crate) fn by_availability(document: &mut Document, available: &[AvailabilityFilter]) {
+    retain(document, |entity| {
+        available
+            .iter()
+            .all(|filter| filter.matches(entity.availability))
+    });
+    document.availability_filter = available.iter().map(|a| a.to_string()).collect();
+}
+
+fn reference(header: &HeaderSummary, name: &str) -> String {
+    format!("{}/{}", header._ref, name)
+}
+
+/// Removes every entity `keep` rejects, then prunes what referred to them.
+fn retain(document: &mut Document, keep: impl Fn(&EntityRef) -> bool) {
+    let removed: Vec<(&str, String)> = entities(document)
+        .iter()
+        .filter(|entity| !keep(entity))
+        .map(|entity| (entity.kind, reference(entity.header, entity.name)))
+        .collect();
+    let is_removed = |kind: &str, header: &HeaderSummary, name: &str| {
+        removed.contains(&(kind, reference(header, name)))
+    };
+
+    document
+        .functions
+        .retain(|f| !is_removed("Function", &f.header, &f.name));
+    document
+        .macros
+        .retain(|m| !is_removed("Macro", &m.header, &m.name));
+    document
+        .enums
+        .retain(|e| !is_removed("Enum", &e.header, &e.name));
+    document
+        .structs
+        .retain(|s| !is_removed("Struct", &s.header, &s.name));
+    document
+        .typedefs
+        .retain(|t| !is_removed("Type", &t.header, &t.name));
+
+    let removed: Vec<String> = removed.into_iter().map(|(_, r)| r).collect();
+    prune(document, &removed);
+}
+
+fn prune(document: &mut Document, removed: &[String]) {
+    // A removed macro can share its reference with a kept function.
+    let dangling: Vec<&String> = removed
+        .iter()
+        .filter(|r| find_entity(document, r).is_none())
+        .collect();
+
+    let header_refs: Vec<String> = entities(document)
+        .iter()
+        .map(|e| e.header._ref.to_string())
+        .collect();
+    document
+        .headers
+        .retain(|h| header_refs.contains(&h._ref.to_string()));
+
+    for function in document.functions.iter_mut() {
+        function.associated.retain(|r| !dangling.contains(&r));
+    }
+
+    for _macro in document.macros.iter_mut() {
+        _macro.associated.retain(|r| !dangling.contains(&r));
+    }
+
+    let errors = document
+        .functions
+        .iter_mut()
+        .flat_map(|f| f.errors.iter_mut())
+        .chain(
+            document
+                .macros
+                .iter_mut()
+                .filter_map(|m| match &mut m.kind {
+                    MacroKind::Function(function) => Some(function.errors.iter_mut()),
+                    MacroKind::Object(_) => None,
+                })
+                .flatten(),
+        );
+
+    for error in errors {
+        if error._ref.as_ref().is_some_and(|r| dangling.contains(&r)) {
+            error._ref = None;
+        }
+    }
+
+    let deprecations = document
+        .functions
+        .iter_mut()
+        .map(|f| &mut f.deprecated)
+        .chain(document.macros.iter_mut().map(|m| &mut m.deprecated))
+        .chain(document.enums.iter_mut().map(|e| &mut e.deprecated))
+        .chain(document.structs.iter_mut().map(|s| &mut s.deprecated))
+        .chain(document.typedefs.iter_mut().map(|t| &mut t.deprecated));
+
+    for deprecated in deprecations.flatten() {
+        if deprecated
+            .replacement
+            .as_ref()
+            .is_some_and(|r| dangling.contains(&r))
+        {
+            deprecated.replacement = None;
+        }
+    }
+
+    let requirements = document
+        .functions
+        .iter_mut()
+        .flat_map(|f| f.feature_test_macros.iter_mut())
+        .chain(
+            document
+                .macros
+                .iter_mut()
+                .flat_map(|m| m.feature_test_macros.iter_mut()),
+        )
+        .chain(
+            document
+                .structs
+                .iter_mut()
+                .flat_map(|s| s.feature_test_macros.iter_mut()),
+        )
+        .chain(
+            document
+                .typedefs
+                .iter_mut()
+                .flat_map(|t| t.feature_test_macros.iter_mut()),
+        );
+
+    for feature_test_macro in requirements {
+        if feature_test_macro
+            ._ref
+            .as_ref()
+            .is_some_and(|r| dangling.contains(&r))
+        {
+            feature_test_macro._ref = None;
+        }
+    }
+
+    for typedef in document.typedefs.iter_mut() {
+        let target = match &typedef.associated_ref {
+            TypedefRef::None(_) => continue,
+            TypedefRef::Enumeration(e) => reference(&e.header, &e.name),
+            TypedefRef::Struct(s) => reference(&s.header, &s.name),
+        };
+
+        if dangling.contains(&&target) {
+            typedef.associated_ref = TypedefRef::None(typedef::None {});
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::{_struct, document, function, header, object_macro, typedef},
+        vocabulary::Vocabulary,
+    };
+    use serde_json::json;
+
+    fn names<'a>(names: impl Iterator<Item = &'a Box<String>>) -> Vec<&'a str> {
+        names.map(|name| name.as_str()).collect()
+    }
+
+    /// `stdio` is portable apart from `gets`; `win` only exists on Windows.
+    fn mixed_document() -> Document {
+        let mut puts = function("stdio", "puts", &["linux", "windows"]);
+        puts["associated"] = json!(["stdio/gets", "stdio/printf", "stdio/getc"]);
+        puts["feature_test_macros"] = json!([
+            { "name": "_WIN32_WINNT", "value": "0x0600", "ref": "win/_WIN32_WINNT" },
+            { "name": "_DEFAULT_SOURCE" },
+        ]);
+
+        let mut printf = function("stdio", "printf", &["linux", "windows"]);
+        printf["deprecated"] = json!({ "reason": "Use gets.", "replacement": "stdio/gets" });
+        printf["errors"] = json!([
+            { "errno": "EWIN", "condition": "Windows failed.", "ref": "win/EWIN" },
+            { "errno": "EIO", "condition": "A write failed.", "ref": "stdio/EIO" },
+        ]);
+
+        let mut eio = object_macro("stdio", "EIO", &["linux", "windows"]);
+        eio["associated"] = json!(["win/EWIN", "stdio/puts"]);
+
+        let handle_info = _struct("win", "handle_info", &["windows"]);
+        let mut handle_t = typedef(
+            "stdio",
+            "handle_t",
+            "struct handle_info",
+            &["linux", "windows"],
+        );
+        handle_t["associated_ref"] = json!({ "struct": handle_info.clone() });
+
+        document(
+            vec![
+                header("stdio", &["linux", "windows"]),
+                header("win", &["windows"]),
+            ],
+            vec![
+                puts,
+                printf,
+                function("stdio", "gets", &["windows"]),
+                function("stdio", "getc", &["linux"]),
+            ],
+            vec![
+                object_macro("win", "EWIN", &["windows"]),
+                eio,
+                object_macro("stdio", "getc", &["windows"]),
+                object_macro("win", "_WIN32_WINNT", &["windows"]),
+            ],
+            vec![handle_info],
+            vec![handle_t],
+        )
+    }
+
+    #[test]
+    fn by_os_removes_entities_and_empty_headers() {
+        let mut document = mixed_document();
+        by_os(&mut document, &[String::from("linux")]);
+
+        assert_eq!(
+            names(document.functions.iter().map(|f| &f.name)),
+            vec!["puts", "printf", "getc"]
+        );
+        assert_eq!(names(document.macros.iter().map(|m| &m.name)), vec!["EIO"]);
+        assert!(document.structs.is_empty());
+        assert_eq!(
+            names(document.headers.iter().map(|h| &h._ref)),
+            vec!["stdio"]
+        );
+        assert_eq!(document.os_filter, vec!["linux"]);
+    }
+
+    #[test]
+    fn by_os_narrows_header_os_affinity() {
+        let mut document = mixed_document();
+        by_os(&mut document, &[String::from("linux")]);
+
+        assert_eq!(document.headers[0].os_affinity, vec!["linux"]);
+    }
+
+    #[test]
+    fn by_arch_keeps_portable_entities_and_narrows_headers() {
+        let mut simd = header("simd", &["linux"]);
+        simd["arch_affinity"] = json!(["aarch64", "x86_64"]);
+        let mut neon_add = function("simd", "neon_add", &["linux"]);
+        neon_add["arch_affinity"] = json!(["aarch64"]);
+        let mut sse_add = function("simd", "sse_add", &["linux"]);
+        sse_add["arch_affinity"] = json!(["x86_64"]);
+
+        let mut document = document(
+            vec![simd],
+            vec![function("simd", "memcpy", &["linux"]), neon_add, sse_add],
+            vec![],
+            vec![],
+            vec![],
+        );
+        by_arch(&mut document, &[String::from("x86_64")]);
+
+        assert_eq!(
+            names(document.functions.iter().map(|f| &f.name)),
+            vec!["memcpy", "sse_add"]
+        );
+        assert_eq!(document.headers[0].arch_affinity, vec!["x86_64"]);
+        assert_eq!(document.arch_filter, vec!["x86_64"]);
+    }
+
+    #[test]
+    fn by_standard_keeps_entities_in_any_requested_standard() {
+        let mut printf = function("stdio", "printf", &["linux"]);
+        printf["standards"] = json!([{ "name": "C", "version": "C89" }]);
+        let mut dprintf = function("stdio", "dprintf", &["linux"]);
+        dprintf["standards"] = json!([{ "name": "POSIX", "version": "POSIX.1-2008" }]);
+
+        let mut document = document(
+            vec![header("stdio", &["linux"])],
+            vec![printf, dprintf, function("stdio", "fpurge", &["linux"])],
+            vec![],
+            vec![],
+            vec![],
+        );
+        by_standard(&mut document, &[StandardFilter::parse("C99").unwrap()]);
+
+        assert_eq!(
+            names(document.functions.iter().map(|f| &f.name)),
+            vec!["printf"]
+        );
+        assert_eq!(document.standard_filter, vec!["C99"]);
+    }
+
+    #[test]
+    fn by_availability_keeps_entities_available_on_every_platform() {
+        let mut getrandom = function("random", "getrandom", &["linux"]);
+        getrandom["availability"] = json!([{ "platform": "glibc", "introduced": "2.25" }]);
+        let mut random = function("random", "random", &["linux"]);
+        random["availability"] = json!([{ "platform": "glibc", "introduced": "2.0" }]);
+
+        let mut document = document(
+            vec![header("random", &["linux"])],
+            vec![getrandom, random, function("random", "rand", &["linux"])],
+            vec![],
+            vec![],
+            vec![],
+        );
+        let glibc =
+            AvailabilityFilter::parse("glibc=2.17", &Vocabulary::load_platform("/nonexistent"))
+                .unwrap();
+        by_availability(&mut document, &[glibc]);
+
+        assert_eq!(
+            names(document.functions.iter().map(|f| &f.name)),
+            vec!["random", "rand"]
+        );
+        assert_eq!(document.availability_filter, vec!["glibc=2.17"]);
+    }
+
+    #[test]
+    fn prune_drops_references_to_removed_entities() {
+        let mut document = mixed_document();
+        by_os(&mut document, &[String::from("linux")]);
+
+        // The removed `getc` macro shares its reference with a kept function.
+        assert_eq!(
+            document.functions[0].associated,
+            vec!["stdio/printf", "stdio/getc"]
+        );
+        assert_eq!(document.macros[0].associated, vec!["stdio/puts"]);
+        assert!(document.functions[1]
+            .deprecated
+            .as_ref()
+            .unwrap()
+            .replacement
+            .is_none());
+        assert!(matches!(
+            document.typedefs[0].associated_ref,
+            TypedefRef::None(_)
+        ));
+        assert_eq!(document.functions[0].feature_test_macros[0]._ref, None);
+        assert_eq!(
+            document.functions[0].feature_test_macros[0].name,
+            "_WIN32_WINNT"
+        );
+        assert_eq!(document.functions[1].errors[0]._ref, None);
+        assert_eq!(
+            document.functions[1].errors[1]._ref.as_deref(),
+            Some("stdio/EIO")
+        );
+    }
+
+    #[test]
+    fn by_os_keeps_everything_the_filter_covers() {
+        let mut document = mixed_document();
+        by_os(
+            &mut document,
+            &[String::from("linux"), String::from("windows")],
+        );
+
+        assert_eq!(document.functions.len(), 4);
+        assert_eq!(document.macros.len(), 4);
+        assert_eq!(document.headers.len(), 2);
+        assert_eq!(document.functions[0].associated.len(), 3);
+        assert_eq!(document.macros[1].associated.len(), 2);
+        assert_eq!(
+            document.functions[0].feature_test_macros[0]._ref.as_deref(),
+            Some("win/_WIN32_WINNT")
+        );
+        assert!(matches!(
+            document.typedefs[0].associated_ref,
+            TypedefRef::Struct(_)
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+//! Corpus fixtures for unit tests, spelled the way `capibara.json` spells
+//! them so tests deserialize into the real definitions.
+
+use crate::Document;
+use serde_json::{json, Value};
+use std::{fs, path::PathBuf};
+
+/// An empty scratch directory unique to the test `name` and this process.
+pub(crate) fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("capibara-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+pub(crate) fn header(_ref: &str, os: &[&str]) -> Value {
+    json!({
+        "ref": _ref,
+        "name": format!("{}.h", _ref),
+        "summary": format!("The {} header.", _ref),
+        "os_affinity": os,
+        "arch_affinity": [],
+        "standards": [],
+        "link": null,
+    })
+}
+
+/// The fields every entity shares; callers add the kind-specific ones.
+pub(crate) fn entity(header: &str, name: &str, os: &[&str]) -> Value {
+    json!({
+        "name": name,
+        "header": { "ref": header, "name": format!("{}.h", header) },
+        "summary": format!("The {} summary.", name),
+        "description": format!("The {} description.", name),
+        "os_affinity": os,
+        "arch_affinity": [],
+        "standards": [],
+        "deprecated": null,
+        "availability": [],
+    })
+}
+
+pub(crate) fn function(header: &str, name: &str, os: &[&str]) -> Value {
+    let mut function = entity(header, name, os);
+    function["returns"] = json!({ "type": "int", "description": "A count." });
+    function["parameters"] = json!([]);
+    function["examples"] = json!([]);
+    function["associated"] = json!([]);
+    function["feature_test_macros"] = json!([]);
+    function["link"] = json!(null);
+    function["attributes"] = json!(null);
+    function["errors"] = json!([]);
+    function
+}
+
+pub(crate) fn object_macro(header: &str, name: &str, os: &[&str]) -> Value {
+    let mut _macro = entity(header, name, os);
+    _macro["kind"] = json!({ "object": {} });
+    _macro["associated"] = json!([]);
+    _macro["feature_test_macros"] = json!([]);
+    _macro
+}
+
+pub(crate) fn function_macro(header: &str, name: &str, parameters: &[&str], os: &[&str]) -> Value {
+    let mut _macro = object_macro(header, name, os);
+    let parameters: Vec<Value> = parameters
+        .iter()
+        .map(|p| json!({ "name": p, "description": format!("The {} argument.", p) }))
+        .collect();
+    _macro["kind"] = json!({
+        "function": {
+            "returns": { "type": "int", "description": "A count." },
+            "parameters": parameters,
+            "examples": [],
+        }
+    });
+    _macro
+}
+
+pub(crate) fn _struct(header: &str, name: &str, os: &[&str]) -> Value {
+    let mut _struct = entity(header, name, os);
+    _struct["fields"] = json!([]);
+    _struct["feature_test_macros"] = json!([]);
+    _struct
+}
+
+pub(crate) fn enumeration(header: &str, name: &str, variants: &[&str], os: &[&str]) -> Value {
+    let mut _enum = entity(header, name, os);
+    let variants: Vec<Value> = variants
+        .iter()
+        .map(|v| json!({ "name": v, "description": "" }))
+        .collect();
+    _enum["variants"] = json!(variants);
+    _enum
+}
+
+pub(crate) fn typedef(header: &str, name: &str, _type: &str, os: &[&str]) -> Value {
+    let mut typedef = entity(header, name, os);
+    typedef["type"] = json!(_type);
+    typedef["associated_ref"] = json!({ "none": {} });
+    typedef["feature_test_macros"] = json!([]);
+    typedef
+}
+
+pub(crate) fn document(
+    headers: Vec<Value>,
+    functions: Vec<Value>,
+    macros: Vec<Value>,
+    structs: Vec<Value>,
+    typedefs: Vec<Value>,
+) -> Document {
+    serde_json::from_value(json!({
+        "build_date": "2024-01-01T00:00:00+00:00",
+        "reference_url": "https://example.org/corpus",
+        "headers": headers,
+        "macros": macros,
+        "enums": [],
+        "structs": structs,
+        "typedefs": typedefs,
+        "functions": functions,
+        "os_filter": [],
+        "arch_filter": [],
+        "standard_filter": [],
+        "availability_filter": [],
+    }))
+    .unwrap()
+}
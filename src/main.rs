@@ -1,15 +1,41 @@
+use availability::AvailabilityFilter;
 use definitions::{
-    _macro::{Macro, YamlMacro},
+    _macro::{Macro, MacroKind, YamlMacro},
     _struct::{Struct, YamlStruct},
+    attributes::Attributes,
+    availability::Availability,
     enumeration::{Enumeration, YamlEnumeration},
+    error_condition::ErrorCondition,
+    feature_test_macro::FeatureTestMacro,
     function::{Function, YamlFunction},
     header::{Header, HeaderSummary, YamlHeader},
+    link::Link,
+    standard::Standard,
     typedef::{Typedef, TypedefRef, YamlTypedef},
 };
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, env, fs, path::PathBuf};
+use standards::StandardFilter;
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+use vocabulary::Vocabulary;
+mod attributes;
+mod availability;
+mod c_parser;
+mod commands;
 mod definitions;
+mod error_conditions;
+mod exporters;
+mod feature_test_macros;
+mod filters;
+#[cfg(test)]
+mod fixtures;
+mod standards;
+mod vocabulary;
 
 #[derive(Serialize, Deserialize)]
 struct Document {
@@ -21,43 +47,297 @@ struct Document {
     structs: Vec<Struct>,
     typedefs: Vec<Typedef>,
     functions: Vec<Function>,
+    os_filter: Vec<String>,
+    arch_filter: Vec<String>,
+    standard_filter: Vec<String>,
+    availability_filter: Vec<String>,
+}
+
+#[derive(Default)]
+struct BuildOptions {
+    html: Option<String>,
+    markdown: Option<String>,
+    man: Option<String>,
+    sqlite: Option<String>,
+    docset: Option<String>,
+    c_headers: Option<String>,
+    matrix: Option<String>,
+    os: Vec<String>,
+    arch: Vec<String>,
+    standard: Vec<String>,
+    available: Vec<String>,
 }
 
 fn main() {
     println!("Capibara Processor");
     let args: Vec<String> = env::args().collect();
-    let filepath = args.get(1).unwrap();
+
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("check") => commands::check::run(&args[2..]),
+        Some("coverage") => commands::coverage::run(&args[2..]),
+        Some("exports") => commands::exports::run(&args[2..]),
+        Some("portability") => commands::portability::run(&args[2..]),
+        Some("scaffold") => commands::scaffold::run(&args[2..]),
+        Some("verify") => commands::verify::run(&args[2..]),
+        _ => build(&args[1..]),
+    }
+}
+
+fn build(args: &[String]) {
+    let filepath = args.first().unwrap();
     println!("Filepath:\t\t{}", filepath);
-    let reference_url = args.get(2).unwrap();
+    let reference_url = args.get(1).unwrap();
     println!("Reference URL:\t{}", reference_url);
+    let options = parse_build_options(&args[2..]);
+
+    let mut document = discover_document(filepath, reference_url);
+    let os_vocabulary = Vocabulary::load_os(filepath);
+
+    if !options.os.is_empty() {
+        match os_vocabulary.normalize(&options.os) {
+            Ok(os) => {
+                filters::by_os(&mut document, &os);
+                println!("Filtered to {}", os.join(", "));
+            }
+            Err(error) => {
+                eprintln!("OS Filter Error: {}", error);
+                process::exit(2);
+            }
+        }
+    }
+
+    if !options.arch.is_empty() {
+        match Vocabulary::load_arch(filepath).normalize(&options.arch) {
+            Ok(arch) => {
+                filters::by_arch(&mut document, &arch);
+                println!("Filtered to {}", arch.join(", "));
+            }
+            Err(error) => {
+                eprintln!("Architecture Filter Error: {}", error);
+                process::exit(2);
+            }
+        }
+    }
+
+    if !options.standard.is_empty() {
+        match options
+            .standard
+            .iter()
+            .map(|standard| StandardFilter::parse(standard))
+            .collect::<Result<Vec<StandardFilter>, String>>()
+        {
+            Ok(standards) => {
+                filters::by_standard(&mut document, &standards);
+                println!("Filtered to {}", document.standard_filter.join(", "));
+            }
+            Err(error) => {
+                eprintln!("Standard Filter Error: {}", error);
+                process::exit(2);
+            }
+        }
+    }
+
+    if !options.available.is_empty() {
+        let platform_vocabulary = Vocabulary::load_platform(filepath);
+        match options
+            .available
+            .iter()
+            .map(|available| AvailabilityFilter::parse(available, &platform_vocabulary))
+            .collect::<Result<Vec<AvailabilityFilter>, String>>()
+        {
+            Ok(available) => {
+                filters::by_availability(&mut document, &available);
+                println!(
+                    "Filtered to available on {}",
+                    document.availability_filter.join(", ")
+                );
+            }
+            Err(error) => {
+                eprintln!("Availability Filter Error: {}", error);
+                process::exit(2);
+            }
+        }
+    }
+
+    let document_json_result = serde_json::to_string(&document);
+
+    match document_json_result {
+        Ok(json) => std::fs::write("./capibara.json", json).unwrap(),
+        Err(error) => {
+            eprintln!("Document Error: {:?}", error);
+        }
+    }
+
+    let search_json_result = serde_json::to_string(&exporters::search::build(&document));
 
+    match search_json_result {
+        Ok(json) => std::fs::write("./capibara-search.json", json).unwrap(),
+        Err(error) => {
+            eprintln!("Search Index Error: {:?}", error);
+        }
+    }
+
+    if let Some(html_path) = &options.html {
+        match exporters::html::export(&document, html_path) {
+            Ok(()) => println!("Wrote HTML site to {}", html_path),
+            Err(error) => eprintln!("HTML Error: {:?}", error),
+        }
+    }
+
+    if let Some(markdown_path) = &options.markdown {
+        match exporters::markdown::export(&document, markdown_path) {
+            Ok(()) => println!("Wrote Markdown to {}", markdown_path),
+            Err(error) => eprintln!("Markdown Error: {:?}", error),
+        }
+    }
+
+    if let Some(man_path) = &options.man {
+        match exporters::man::export(&document, man_path) {
+            Ok(()) => println!("Wrote man pages to {}", man_path),
+            Err(error) => eprintln!("Man Page Error: {:?}", error),
+        }
+    }
+
+    if let Some(sqlite_path) = &options.sqlite {
+        match exporters::sqlite::export(&document, sqlite_path) {
+            Ok(()) => println!("Wrote SQLite database to {}", sqlite_path),
+            Err(error) => eprintln!("SQLite Error: {:?}", error),
+        }
+    }
+
+    if let Some(docset_path) = &options.docset {
+        match exporters::docset::export(&document, docset_path) {
+            Ok(()) => println!("Wrote docset to {}", docset_path),
+            Err(error) => eprintln!("Docset Error: {:?}", error),
+        }
+    }
+
+    if let Some(c_headers_path) = &options.c_headers {
+        match exporters::c_header::export(&document, c_headers_path) {
+            Ok(()) => println!("Wrote C headers to {}", c_headers_path),
+            Err(error) => eprintln!("C Header Error: {:?}", error),
+        }
+    }
+
+    if let Some(matrix_path) = &options.matrix {
+        match exporters::matrix::export(&document, &os_vocabulary, matrix_path) {
+            Ok(()) => println!("Wrote OS compatibility matrix to {}", matrix_path),
+            Err(error) => eprintln!("Matrix Error: {:?}", error),
+        }
+    }
+}
+
+fn parse_build_options(args: &[String]) -> BuildOptions {
+    let mut options = BuildOptions::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--html" => options.html = args.next().cloned(),
+            "--markdown" => options.markdown = args.next().cloned(),
+            "--man" => options.man = args.next().cloned(),
+            "--sqlite" => options.sqlite = args.next().cloned(),
+            "--docset" => options.docset = args.next().cloned(),
+            "--c-headers" => options.c_headers = args.next().cloned(),
+            "--matrix" => options.matrix = args.next().cloned(),
+            "--os" => options.os.extend(args.next().cloned()),
+            "--arch" => options.arch.extend(args.next().cloned()),
+            "--standard" => options.standard.extend(args.next().cloned()),
+            "--available" => options.available.extend(args.next().cloned()),
+            _ => eprintln!("Unknown option: {}", arg),
+        }
+    }
+
+    options
+}
+
+fn discover_document(filepath: &String, reference_url: &str) -> Document {
     let mut os_affinities = HashMap::new();
 
     let header_paths = find_header_paths(filepath);
     println!("Found {} header paths", header_paths.len());
-    let macros = discover_macros(filepath, header_paths.clone(), &mut os_affinities);
+    let mut affinities = Affinities::load(filepath, header_paths.clone());
+    let mut macros = discover_macros(
+        filepath,
+        header_paths.clone(),
+        &mut os_affinities,
+        &mut affinities,
+    );
+    // Macros can require other macros, so theirs resolve once all are known.
+    let requirements: Vec<Vec<FeatureTestMacro>> = macros
+        .iter()
+        .map(|m| feature_test_macros::resolve(m.feature_test_macros.clone(), &macros))
+        .collect();
+    for (_macro, feature_test_macros) in macros.iter_mut().zip(requirements) {
+        _macro.feature_test_macros = feature_test_macros;
+    }
+    // So can their errors, which name errno values documented as macros.
+    let errors: Vec<Vec<ErrorCondition>> = macros
+        .iter()
+        .map(|m| match &m.kind {
+            MacroKind::Function(function) => {
+                error_conditions::resolve(function.errors.clone(), &macros)
+            }
+            MacroKind::Object(_) => Vec::new(),
+        })
+        .collect();
+    for (_macro, errors) in macros.iter_mut().zip(errors) {
+        if let MacroKind::Function(function) = &mut _macro.kind {
+            for error in errors.iter().filter(|e| e._ref.is_none()) {
+                eprintln!(
+                    "Macro errno look up failed for: {} ({})",
+                    error.errno, _macro.name
+                );
+            }
+            function.errors = errors;
+        }
+    }
     println!("Found {} macros", macros.len());
-    let enums = discover_enums(filepath, header_paths.clone(), &mut os_affinities);
+    let enums = discover_enums(
+        filepath,
+        header_paths.clone(),
+        &mut os_affinities,
+        &mut affinities,
+    );
     println!("Found {} enums", enums.len());
-    let structs = discover_structs(filepath, header_paths.clone(), &mut os_affinities);
+    let structs = discover_structs(
+        filepath,
+        header_paths.clone(),
+        &mut os_affinities,
+        &mut affinities,
+        &macros,
+    );
     println!("Found {} structs", structs.len());
 
     let typedefs = discover_typedefs(
         filepath,
         header_paths.clone(),
         &mut os_affinities,
+        &mut affinities,
         &enums,
         &structs,
+        &macros,
     );
     println!("Found {} typedefs", typedefs.len());
 
-    let functions = discover_functions(filepath, header_paths.clone(), &mut os_affinities);
+    let functions = discover_functions(
+        filepath,
+        header_paths.clone(),
+        &mut os_affinities,
+        &mut affinities,
+        &macros,
+    );
     println!("Found {} functions", functions.len());
 
-    let headers = discover_headers(filepath, header_paths.clone(), &mut os_affinities);
+    let headers = discover_headers(
+        filepath,
+        header_paths.clone(),
+        &mut os_affinities,
+        &affinities,
+    );
     println!("Found {} headers", headers.len());
 
-    let document = Document {
+    Document {
         build_date: chrono::Utc::now().to_rfc3339(),
         reference_url: reference_url.to_string(),
         headers: headers,
@@ -66,15 +346,10 @@ fn main() {
         structs: structs,
         typedefs: typedefs,
         functions: functions,
-    };
-
-    let document_json_result = serde_json::to_string(&document);
-
-    match document_json_result {
-        Ok(json) => std::fs::write("./capibara.json", json).unwrap(),
-        Err(error) => {
-            eprintln!("Document Error: {:?}", error);
-        }
+        os_filter: Vec::new(),
+        arch_filter: Vec::new(),
+        standard_filter: Vec::new(),
+        availability_filter: Vec::new(),
     }
 }
 
@@ -128,6 +403,7 @@ fn discover_headers(
     filepath: &String,
     header_paths: Vec<String>,
     os_affinities: &mut HashMap<String, Vec<String>>,
+    affinities: &Affinities,
 ) -> Vec<Header> {
     let mut headers_to_return = Vec::new();
 
@@ -164,7 +440,21 @@ fn discover_headers(
                             + ".h",
                     ),
                     summary: yaml.summary,
-                    os_affinity: get_header_os_affinity(os_affinities, &header_path.clone()),
+                    os_affinity: match affinities.default_os_affinities.get(&header_path) {
+                        Some(default_os_affinity) => default_os_affinity.clone(),
+                        None => get_header_os_affinity(
+                            os_affinities,
+                            &affinities.os_vocabulary,
+                            &header_path.clone(),
+                        ),
+                    },
+                    arch_affinity: affinities.header_arch_affinity(&header_path),
+                    standards: affinities
+                        .default_standards
+                        .get(&header_path)
+                        .cloned()
+                        .unwrap_or_default(),
+                    link: affinities.default_links.get(&header_path).cloned(),
                 };
 
                 headers_to_return.push(header)
@@ -185,6 +475,8 @@ fn discover_functions(
     filepath: &String,
     header_paths: Vec<String>,
     os_affinities: &mut HashMap<String, Vec<String>>,
+    affinities: &mut Affinities,
+    macros: &[Macro],
 ) -> Vec<Function> {
     let mut functions_to_return = Vec::new();
 
@@ -246,6 +538,44 @@ fn discover_functions(
 
                     match function_yaml_result {
                         Ok(yaml) => {
+                            let Some(Resolved {
+                                os_affinity,
+                                arch_affinity,
+                                standards,
+                                availability,
+                                link,
+                                attributes,
+                            }) = affinities.resolve(
+                                &path,
+                                &file_path,
+                                "Function",
+                                Declared {
+                                    os_affinity: yaml.os_affinity.as_deref(),
+                                    arch_affinity: yaml.arch_affinity.as_deref(),
+                                    standards: yaml.standards.as_deref(),
+                                    availability: yaml.availability.as_deref(),
+                                    link: yaml.link.as_ref(),
+                                    attributes: yaml.attributes.as_ref(),
+                                },
+                            )
+                            else {
+                                continue;
+                            };
+
+                            if let Err(error) = error_conditions::check_convention(&yaml.returns) {
+                                eprintln!("Function Error ({:?}): {}", file_path, error);
+                                continue;
+                            }
+
+                            let errors = error_conditions::resolve(yaml.errors, macros);
+
+                            for error in errors.iter().filter(|e| e._ref.is_none()) {
+                                eprintln!(
+                                    "Function errno look up failed for: {} ({:?})",
+                                    error.errno, file_path
+                                );
+                            }
+
                             let function = Function {
                                 name: Box::new(
                                     file_path
@@ -264,19 +594,32 @@ fn discover_functions(
                                 description: yaml.description,
                                 associated: yaml.associated,
                                 examples: yaml.examples,
-                                os_affinity: yaml.os_affinity.clone(),
+                                os_affinity: os_affinity.clone(),
+                                arch_affinity,
+                                standards,
+                                deprecated: yaml.deprecated,
+                                availability,
+                                feature_test_macros: feature_test_macros::resolve(
+                                    yaml.feature_test_macros,
+                                    macros,
+                                ),
+                                link,
+                                attributes,
+                                errors,
                             };
 
+                            affinities.record_arch_affinity(&path, &function.arch_affinity);
+
                             if os_affinities.contains_key(&path.clone()) {
                                 let header_affinity = os_affinities.get_mut(&path).unwrap();
-                                for value in yaml.os_affinity.iter() {
+                                for value in os_affinity.iter() {
                                     let new_value = value.clone();
                                     if !header_affinity.contains(&new_value) {
                                         header_affinity.push(new_value);
                                     }
                                 }
                             } else {
-                                os_affinities.insert(path.clone(), yaml.os_affinity.clone());
+                                os_affinities.insert(path.clone(), os_affinity.clone());
                             }
 
                             functions_to_return.push(function);
@@ -297,6 +640,7 @@ fn discover_macros(
     filepath: &String,
     header_paths: Vec<String>,
     os_affinities: &mut HashMap<String, Vec<String>>,
+    affinities: &mut Affinities,
 ) -> Vec<Macro> {
     let mut macros_to_return = Vec::new();
 
@@ -357,7 +701,49 @@ fn discover_macros(
                     let enum_yaml_result = serde_yaml::from_str::<YamlMacro>(&file_contents);
 
                     match enum_yaml_result {
-                        Ok(yaml) => {
+                        Ok(mut yaml) => {
+                            let declared_attributes = match &yaml.kind {
+                                MacroKind::Function(function) => function.attributes.as_deref(),
+                                MacroKind::Object(_) => None,
+                            };
+
+                            let Some(Resolved {
+                                os_affinity,
+                                arch_affinity,
+                                standards,
+                                availability,
+                                attributes,
+                                ..
+                            }) = affinities.resolve(
+                                &path,
+                                &file_path,
+                                "Macro",
+                                Declared {
+                                    os_affinity: yaml.os_affinity.as_deref(),
+                                    arch_affinity: yaml.arch_affinity.as_deref(),
+                                    standards: yaml.standards.as_deref(),
+                                    availability: yaml.availability.as_deref(),
+                                    link: None,
+                                    attributes: declared_attributes,
+                                },
+                            )
+                            else {
+                                continue;
+                            };
+
+                            if let MacroKind::Function(function) = &mut yaml.kind {
+                                function.attributes = attributes.map(Box::new);
+                            }
+
+                            if let MacroKind::Function(function) = &yaml.kind {
+                                if let Err(error) =
+                                    error_conditions::check_convention(&function.returns)
+                                {
+                                    eprintln!("Macro Error ({:?}): {}", file_path, error);
+                                    continue;
+                                }
+                            }
+
                             let _macro = Macro {
                                 name: Box::new(
                                     file_path
@@ -373,19 +759,27 @@ fn discover_macros(
                                 summary: yaml.summary,
                                 kind: yaml.kind,
                                 description: yaml.description,
-                                os_affinity: yaml.os_affinity.clone(),
+                                associated: yaml.associated,
+                                os_affinity: os_affinity.clone(),
+                                arch_affinity,
+                                standards,
+                                deprecated: yaml.deprecated,
+                                availability,
+                                feature_test_macros: yaml.feature_test_macros,
                             };
 
+                            affinities.record_arch_affinity(&path, &_macro.arch_affinity);
+
                             if os_affinities.contains_key(&path.clone()) {
                                 let header_affinity = os_affinities.get_mut(&path).unwrap();
-                                for value in yaml.os_affinity.iter() {
+                                for value in os_affinity.iter() {
                                     let new_value = value.clone();
                                     if !header_affinity.contains(&new_value) {
                                         header_affinity.push(value.to_string());
                                     }
                                 }
                             } else {
-                                os_affinities.insert(path.clone(), yaml.os_affinity);
+                                os_affinities.insert(path.clone(), os_affinity);
                             }
 
                             macros_to_return.push(_macro);
@@ -406,6 +800,7 @@ fn discover_enums(
     filepath: &String,
     header_paths: Vec<String>,
     os_affinities: &mut HashMap<String, Vec<String>>,
+    affinities: &mut Affinities,
 ) -> Vec<Enumeration> {
     let mut enums_to_return = Vec::new();
 
@@ -467,6 +862,29 @@ fn discover_enums(
 
                     match enum_yaml_result {
                         Ok(yaml) => {
+                            let Some(Resolved {
+                                os_affinity,
+                                arch_affinity,
+                                standards,
+                                availability,
+                                ..
+                            }) = affinities.resolve(
+                                &path,
+                                &file_path,
+                                "Enum",
+                                Declared {
+                                    os_affinity: yaml.os_affinity.as_deref(),
+                                    arch_affinity: yaml.arch_affinity.as_deref(),
+                                    standards: yaml.standards.as_deref(),
+                                    availability: yaml.availability.as_deref(),
+                                    link: None,
+                                    attributes: None,
+                                },
+                            )
+                            else {
+                                continue;
+                            };
+
                             let enumeration = Enumeration {
                                 name: Box::new(
                                     file_path
@@ -482,19 +900,25 @@ fn discover_enums(
                                 summary: yaml.summary,
                                 variants: yaml.variants,
                                 description: yaml.description,
-                                os_affinity: yaml.os_affinity.clone(),
+                                os_affinity: os_affinity.clone(),
+                                arch_affinity,
+                                standards,
+                                deprecated: yaml.deprecated,
+                                availability,
                             };
 
+                            affinities.record_arch_affinity(&path, &enumeration.arch_affinity);
+
                             if os_affinities.contains_key(&path.clone()) {
                                 let header_affinity = os_affinities.get_mut(&path).unwrap();
-                                for value in yaml.os_affinity.iter() {
+                                for value in os_affinity.iter() {
                                     let new_value = value.clone();
                                     if !header_affinity.contains(&new_value) {
                                         header_affinity.push(value.to_string());
                                     }
                                 }
                             } else {
-                                os_affinities.insert(path.clone(), yaml.os_affinity);
+                                os_affinities.insert(path.clone(), os_affinity);
                             }
 
                             enums_to_return.push(enumeration);
@@ -515,6 +939,8 @@ fn discover_structs(
     filepath: &String,
     header_paths: Vec<String>,
     os_affinities: &mut HashMap<String, Vec<String>>,
+    affinities: &mut Affinities,
+    macros: &[Macro],
 ) -> Vec<Struct> {
     let mut structs_to_return = Vec::new();
 
@@ -576,6 +1002,29 @@ fn discover_structs(
 
                     match struct_yaml_result {
                         Ok(yaml) => {
+                            let Some(Resolved {
+                                os_affinity,
+                                arch_affinity,
+                                standards,
+                                availability,
+                                ..
+                            }) = affinities.resolve(
+                                &path,
+                                &file_path,
+                                "Struct",
+                                Declared {
+                                    os_affinity: yaml.os_affinity.as_deref(),
+                                    arch_affinity: yaml.arch_affinity.as_deref(),
+                                    standards: yaml.standards.as_deref(),
+                                    availability: yaml.availability.as_deref(),
+                                    link: None,
+                                    attributes: None,
+                                },
+                            )
+                            else {
+                                continue;
+                            };
+
                             let _struct = Struct {
                                 name: Box::new(
                                     file_path
@@ -591,19 +1040,29 @@ fn discover_structs(
                                 summary: yaml.summary,
                                 fields: yaml.fields,
                                 description: yaml.description,
-                                os_affinity: yaml.os_affinity.clone(),
+                                os_affinity: os_affinity.clone(),
+                                arch_affinity,
+                                standards,
+                                deprecated: yaml.deprecated,
+                                availability,
+                                feature_test_macros: feature_test_macros::resolve(
+                                    yaml.feature_test_macros,
+                                    macros,
+                                ),
                             };
 
+                            affinities.record_arch_affinity(&path, &_struct.arch_affinity);
+
                             if os_affinities.contains_key(&path.clone()) {
                                 let header_affinity = os_affinities.get_mut(&path).unwrap();
-                                for value in yaml.os_affinity.iter() {
+                                for value in os_affinity.iter() {
                                     let new_value = value.clone();
                                     if !header_affinity.contains(&new_value) {
                                         header_affinity.push(value.to_string());
                                     }
                                 }
                             } else {
-                                os_affinities.insert(path.clone(), yaml.os_affinity);
+                                os_affinities.insert(path.clone(), os_affinity);
                             }
 
                             structs_to_return.push(_struct);
@@ -624,8 +1083,10 @@ fn discover_typedefs(
     filepath: &String,
     header_paths: Vec<String>,
     os_affinities: &mut HashMap<String, Vec<String>>,
+    affinities: &mut Affinities,
     enums: &Vec<Enumeration>,
     structs: &Vec<Struct>,
+    macros: &[Macro],
 ) -> Vec<Typedef> {
     let mut typedefs_to_return = Vec::new();
 
@@ -687,6 +1148,29 @@ fn discover_typedefs(
 
                     match typedef_yaml_result {
                         Ok(yaml) => {
+                            let Some(Resolved {
+                                os_affinity,
+                                arch_affinity,
+                                standards,
+                                availability,
+                                ..
+                            }) = affinities.resolve(
+                                &path,
+                                &file_path,
+                                "Typedef",
+                                Declared {
+                                    os_affinity: yaml.os_affinity.as_deref(),
+                                    arch_affinity: yaml.arch_affinity.as_deref(),
+                                    standards: yaml.standards.as_deref(),
+                                    availability: yaml.availability.as_deref(),
+                                    link: None,
+                                    attributes: None,
+                                },
+                            )
+                            else {
+                                continue;
+                            };
+
                             let mut associated_ref =
                                 TypedefRef::None(definitions::typedef::None {});
 
@@ -745,19 +1229,27 @@ fn discover_typedefs(
                                 _type: yaml._type,
                                 associated_ref: associated_ref,
                                 description: yaml.description,
-                                os_affinity: yaml.os_affinity.clone(),
+                                os_affinity: os_affinity.clone(),
+                                arch_affinity,
+                                standards,
+                                deprecated: yaml.deprecated,
+                                availability,
+                                feature_test_macros: feature_test_macros::resolve(
+                                    yaml.feature_test_macros,
+                                    macros,
+                                ),
                             };
 
                             if os_affinities.contains_key(&path.clone()) {
                                 let header_affinity = os_affinities.get_mut(&path).unwrap();
-                                for value in yaml.os_affinity.iter() {
+                                for value in os_affinity.iter() {
                                     let new_value = value.clone();
                                     if !header_affinity.contains(&new_value) {
                                         header_affinity.push(value.to_string());
                                     }
                                 }
                             } else {
-                                os_affinities.insert(path.clone(), yaml.os_affinity);
+                                os_affinities.insert(path.clone(), os_affinity);
                             }
 
                             typedefs_to_return.push(typedef);
@@ -774,13 +1266,248 @@ fn discover_typedefs(
     typedefs_to_return
 }
 
+/// Vocabularies and per-header affinity state used during discovery. Maps are
+/// keyed by `meta.yaml` path, like `os_affinities`.
+struct Affinities {
+    os_vocabulary: Vocabulary,
+    arch_vocabulary: Vocabulary,
+    platform_vocabulary: Vocabulary,
+    /// The optional `os_affinity` default each `meta.yaml` declares.
+    default_os_affinities: HashMap<String, Vec<String>>,
+    /// The `standards` each `meta.yaml` declares, inherited by definitions
+    /// that omit theirs.
+    default_standards: HashMap<String, Vec<Standard>>,
+    /// The `link` each `meta.yaml` declares, inherited by functions that
+    /// omit theirs.
+    default_links: HashMap<String, Link>,
+    /// Union of the children's `arch_affinity`; `None` once any child is
+    /// architecture independent.
+    arch_affinities: HashMap<String, Option<Vec<String>>>,
+}
+
+/// The fields a definition file declares that are normalised against the
+/// header defaults and the corpus vocabularies.
+struct Declared<'a> {
+    os_affinity: Option<&'a [String]>,
+    arch_affinity: Option<&'a [String]>,
+    standards: Option<&'a [Standard]>,
+    availability: Option<&'a [Availability]>,
+    link: Option<&'a Link>,
+    attributes: Option<&'a Attributes>,
+}
+
+/// A definition's declared fields, normalised.
+struct Resolved {
+    os_affinity: Vec<String>,
+    arch_affinity: Vec<String>,
+    standards: Vec<Standard>,
+    availability: Vec<Availability>,
+    link: Option<Link>,
+    attributes: Option<Attributes>,
+}
+
+impl Affinities {
+    fn load(filepath: &str, header_paths: Vec<String>) -> Affinities {
+        let os_vocabulary = Vocabulary::load_os(filepath);
+        let mut default_os_affinities = HashMap::new();
+        let mut default_standards = HashMap::new();
+        let mut default_links = HashMap::new();
+
+        for header_path in header_paths {
+            let header_file_contents =
+                std::fs::read_to_string(PathBuf::from(&header_path)).unwrap();
+            let yaml = match serde_yaml::from_str::<YamlHeader>(&header_file_contents) {
+                Ok(yaml) => yaml,
+                Err(_) => continue,
+            };
+
+            if let Some(os_affinity) = yaml.os_affinity {
+                match os_vocabulary.normalize(&os_affinity) {
+                    Ok(os_affinity) => {
+                        default_os_affinities.insert(header_path.clone(), os_affinity);
+                    }
+                    Err(error) => eprintln!("Header Error ({:?}): {}", header_path, error),
+                }
+            }
+
+            if let Some(standards) = yaml.standards {
+                match standards::normalize(&standards) {
+                    Ok(standards) => {
+                        default_standards.insert(header_path.clone(), standards);
+                    }
+                    Err(error) => eprintln!("Header Error ({:?}): {}", header_path, error),
+                }
+            }
+
+            if let Some(link) = yaml.link {
+                match normalize_link(link, &os_vocabulary) {
+                    Ok(link) => {
+                        default_links.insert(header_path, link);
+                    }
+                    Err(error) => eprintln!("Header Error ({:?}): {}", header_path, error),
+                }
+            }
+        }
+
+        Affinities {
+            os_vocabulary,
+            arch_vocabulary: Vocabulary::load_arch(filepath),
+            platform_vocabulary: Vocabulary::load_platform(filepath),
+            default_os_affinities,
+            default_standards,
+            default_links,
+            arch_affinities: HashMap::new(),
+        }
+    }
+
+    /// A definition's declared fields, normalised. Problems are reported as
+    /// `kind` errors against `file`, which is then skipped.
+    fn resolve(
+        &self,
+        header_path: &String,
+        file: &fs::DirEntry,
+        kind: &str,
+        declared: Declared,
+    ) -> Option<Resolved> {
+        match self.try_resolve(header_path, &file.path(), declared) {
+            Ok(resolved) => Some(resolved),
+            Err(error) => {
+                eprintln!("{} Error ({:?}): {}", kind, file, error);
+                None
+            }
+        }
+    }
+
+    /// `os_affinity` is inherited from the header default when the file
+    /// omits it; claims outside an explicit header default are kept but
+    /// warned about. An omitted or empty `arch_affinity` means the
+    /// definition is architecture independent. `standards` are the header's
+    /// when the file omits them, and so is `link`; `availability` and
+    /// `attributes` are checked against their vocabularies.
+    fn try_resolve(
+        &self,
+        header_path: &String,
+        file_path: &Path,
+        declared: Declared,
+    ) -> Result<Resolved, String> {
+        let default_os_affinity = self.default_os_affinities.get(header_path);
+
+        let os_affinity = match declared.os_affinity {
+            Some(os_affinity) => self.os_vocabulary.normalize(os_affinity)?,
+            None => default_os_affinity.cloned().unwrap_or_default(),
+        };
+
+        if let Some(default_os_affinity) = default_os_affinity {
+            for os in os_affinity.iter() {
+                if !default_os_affinity.contains(os) {
+                    eprintln!(
+                        "OS Affinity Warning ({:?}): claims {}, which meta.yaml does not support",
+                        file_path, os
+                    );
+                }
+            }
+        }
+
+        let arch_affinity = self
+            .arch_vocabulary
+            .normalize(declared.arch_affinity.unwrap_or_default())?;
+
+        let standards = match declared.standards {
+            Some(standards) => standards::normalize(standards)?,
+            None => self
+                .default_standards
+                .get(header_path)
+                .cloned()
+                .unwrap_or_default(),
+        };
+
+        let availability = availability::normalize(
+            declared.availability.unwrap_or_default(),
+            &self.platform_vocabulary,
+        )?;
+
+        let link = match declared.link {
+            Some(link) => Some(normalize_link(link.clone(), &self.os_vocabulary)?),
+            None => self.default_links.get(header_path).cloned(),
+        };
+
+        let attributes = declared
+            .attributes
+            .cloned()
+            .map(attributes::normalize)
+            .transpose()?;
+
+        Ok(Resolved {
+            os_affinity,
+            arch_affinity,
+            standards,
+            availability,
+            link,
+            attributes,
+        })
+    }
+
+    /// Adds an accepted definition's arches to its header's union. Any
+    /// architecture independent definition makes the header independent too.
+    fn record_arch_affinity(&mut self, header_path: &str, arch_affinity: &[String]) {
+        let header_arch_affinity = self
+            .arch_affinities
+            .entry(header_path.to_string())
+            .or_insert_with(|| Some(Vec::new()));
+
+        if arch_affinity.is_empty() {
+            *header_arch_affinity = None;
+        } else if let Some(header_arch_affinity) = header_arch_affinity {
+            for arch in arch_affinity.iter() {
+                if !header_arch_affinity.contains(arch) {
+                    header_arch_affinity.push(arch.clone());
+                }
+            }
+        }
+    }
+
+    /// The merged `arch_affinity` of a header's definitions, empty when any
+    /// of them is architecture independent.
+    fn header_arch_affinity(&self, header_path: &String) -> Vec<String> {
+        match self.arch_affinities.get(header_path) {
+            Some(Some(arch_affinity)) => {
+                let mut arch_affinity = arch_affinity.clone();
+                self.arch_vocabulary.sort(&mut arch_affinity);
+                arch_affinity
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Normalises the OSes of a `link`'s overrides. Each OS may be overridden
+/// once.
+fn normalize_link(mut link: Link, os_vocabulary: &Vocabulary) -> Result<Link, String> {
+    let mut overridden: Vec<String> = Vec::new();
+
+    for os_link in link.os_overrides.iter_mut() {
+        os_link.os = os_vocabulary.normalize(&os_link.os)?;
+
+        for os in os_link.os.iter() {
+            if overridden.contains(os) {
+                return Err(format!("link is overridden more than once for {}", os));
+            }
+            overridden.push(os.clone());
+        }
+    }
+
+    Ok(link)
+}
+
 fn get_header_os_affinity(
     os_affinities: &mut HashMap<String, Vec<String>>,
+    os_vocabulary: &Vocabulary,
     header_path: &String,
 ) -> Vec<String> {
     let value = os_affinities.get_mut(header_path);
 
     if let Some(value) = value {
+        os_vocabulary.sort(value);
         return value.to_owned();
     } else {
         return Vec::new();
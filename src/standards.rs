@@ -0,0 +1,297 @@
+//! The standards a definition can claim in its `standards` list, and the
+//! `--standard` build filter over them.
+//!
+//! ```yaml
+//! standards:
+//!   - name: C
+//!     version: C99
+//!   - name: POSIX
+//!     version: POSIX.1-2008
+//!     option: XSI
+//! ```
+//!
+//! Versions of a standard are listed oldest first; a definition claiming a
+//! version is taken to be part of every later one. Names, versions and
+//! option groups match case-insensitively.
+
+use crate::definitions::standard::Standard;
+use std::fmt;
+
+struct KnownStandard {
+    name: &'static str,
+    versions: &'static [(&'static str, &'static [&'static str])],
+    options: &'static [&'static str],
+}
+
+const KNOWN_STANDARDS: &[KnownStandard] = &[
+    KnownStandard {
+        name: "C",
+        versions: &[
+            ("C89", &["C90", "ANSI"]),
+            ("C99", &[]),
+            ("C11", &[]),
+            ("C17", &["C18"]),
+            ("C23", &["C2x"]),
+        ],
+        options: &[],
+    },
+    KnownStandard {
+        name: "POSIX",
+        versions: &[
+            ("POSIX.1-2001", &["2001", "SUSv3"]),
+            ("POSIX.1-2008", &["2008", "SUSv4"]),
+            ("POSIX.1-2017", &["2017"]),
+            ("POSIX.1-2024", &["2024"]),
+        ],
+        options: &[
+            "ADV", "CX", "FSC", "IP6", "ML", "MLR", "MON", "MSG", "MX", "RPP", "RS", "SHM", "SIO",
+            "SPN", "SS", "TCT", "TEF", "THR", "TMO", "TMR", "TPI", "TPP", "TPS", "TSA", "TSF",
+            "TSH", "TSP", "TSS", "TYM", "XSI",
+        ],
+    },
+];
+
+impl KnownStandard {
+    fn find(name: &str) -> Option<(usize, &'static KnownStandard)> {
+        KNOWN_STANDARDS
+            .iter()
+            .enumerate()
+            .find(|(_, known)| known.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Position and canonical name of `version`.
+    fn version(&self, version: &str) -> Option<(usize, &'static str)> {
+        let version = version.trim();
+
+        self.versions
+            .iter()
+            .enumerate()
+            .find(|(_, (name, aliases))| {
+                name.eq_ignore_ascii_case(version)
+                    || aliases
+                        .iter()
+                        .any(|alias| alias.eq_ignore_ascii_case(version))
+            })
+            .map(|(i, (name, _))| (i, *name))
+    }
+
+    fn option(&self, option: &str) -> Option<&'static str> {
+        self.options
+            .iter()
+            .find(|known| known.eq_ignore_ascii_case(option.trim()))
+            .copied()
+    }
+}
+
+/// Canonical form of a single claim, or why it isn't a known standard.
+fn canonical(standard: &Standard) -> Result<Standard, String> {
+    let (_, known) = KnownStandard::find(&standard.name)
+        .ok_or_else(|| format!("unknown standard \"{}\"", standard.name))?;
+    let (_, version) = known
+        .version(&standard.version)
+        .ok_or_else(|| format!("unknown {} version \"{}\"", known.name, standard.version))?;
+    let option = match &standard.option {
+        Some(option) => Some(
+            known
+                .option(option)
+                .ok_or_else(|| format!("unknown {} option group \"{}\"", known.name, option))?,
+        ),
+        None => None,
+    };
+
+    Ok(Standard {
+        name: known.name.to_string(),
+        version: version.to_string(),
+        option: option.map(String::from),
+    })
+}
+
+/// Sort key putting claims in the order of the known list.
+fn position(standard: &Standard) -> (usize, usize) {
+    match KnownStandard::find(&standard.name) {
+        Some((i, known)) => (
+            i,
+            known
+                .version(&standard.version)
+                .map(|(v, _)| v)
+                .unwrap_or(usize::MAX),
+        ),
+        None => (usize::MAX, usize::MAX),
+    }
+}
+
+/// Canonicalises every claim, dropping duplicates and sorting them by
+/// standard and version. Fails on the first unknown name, version or option
+/// group.
+pub(crate) fn normalize(standards: &[Standard]) -> Result<Vec<Standard>, String> {
+    let mut normalized: Vec<Standard> = Vec::new();
+
+    for standard in standards.iter() {
+        let standard = canonical(standard)?;
+
+        if !normalized.contains(&standard) {
+            normalized.push(standard);
+        }
+    }
+
+    normalized.sort_by_key(position);
+
+    Ok(normalized)
+}
+
+/// One `--standard` argument: `C11`, `POSIX.1-2008`, `POSIX.1-2008+XSI` or a
+/// bare standard name such as `POSIX` for any version.
+pub(crate) struct StandardFilter {
+    name: &'static str,
+    /// Position of the newest version let through, `None` for any.
+    version: Option<usize>,
+    options: Vec<&'static str>,
+}
+
+impl StandardFilter {
+    pub fn parse(argument: &str) -> Result<StandardFilter, String> {
+        let mut parts = argument.split('+');
+        let target = parts.next().unwrap_or_default();
+
+        let (name, version) = match KnownStandard::find(target) {
+            Some((_, known)) => (known, None),
+            None => KNOWN_STANDARDS
+                .iter()
+                .find_map(|known| known.version(target).map(|(v, _)| (known, Some(v))))
+                .ok_or_else(|| format!("unknown standard \"{}\"", target))?,
+        };
+
+        let options = parts
+            .map(|option| {
+                name.option(option)
+                    .ok_or_else(|| format!("unknown {} option group \"{}\"", name.name, option))
+            })
+            .collect::<Result<Vec<&str>, String>>()?;
+
+        Ok(StandardFilter {
+            name: name.name,
+            version,
+            options,
+        })
+    }
+
+    /// Whether a definition with these (normalised) claims belongs to the
+    /// filtered standard: it must claim the same standard at the filtered
+    /// version or an older one, either in the base or in one of the
+    /// filter's option groups.
+    pub fn matches(&self, standards: &[Standard]) -> bool {
+        standards.iter().any(|standard| {
+            standard.name == self.name
+                && match self.version {
+                    Some(version) => position(standard).1 <= version,
+                    None => true,
+                }
+                && match &standard.option {
+                    Some(option) => self.options.contains(&option.as_str()),
+                    None => true,
+                }
+        })
+    }
+}
+
+impl fmt::Display for StandardFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, known) = KnownStandard::find(self.name).unwrap();
+
+        match self.version {
+            Some(version) => write!(f, "{}", known.versions[version].0)?,
+            None => write!(f, "{}", self.name)?,
+        }
+
+        for option in self.options.iter() {
+            write!(f, "+{}", option)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standard(name: &str, version: &str, option: Option<&str>) -> Standard {
+        Standard {
+            name: name.to_string(),
+            version: version.to_string(),
+            option: option.map(String::from),
+        }
+    }
+
+    fn describe(standards: &[Standard]) -> Vec<String> {
+        standards
+            .iter()
+            .map(|s| match &s.option {
+                Some(option) => format!("{} {}+{}", s.name, s.version, option),
+                None => format!("{} {}", s.name, s.version),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn normalize_canonicalises_aliases_and_sorts() {
+        let normalized = normalize(&[
+            standard("posix", "susv4", Some("xsi")),
+            standard("C", "c18", None),
+            standard("C", "ANSI", None),
+            standard("C", "C17", None),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            describe(&normalized),
+            vec!["C C89", "C C17", "POSIX POSIX.1-2008+XSI"]
+        );
+    }
+
+    #[test]
+    fn normalize_rejects_unknown_claims() {
+        assert_eq!(
+            normalize(&[standard("Bogus", "1", None)]).err(),
+            Some(String::from("unknown standard \"Bogus\""))
+        );
+        assert_eq!(
+            normalize(&[standard("C", "C95", None)]).err(),
+            Some(String::from("unknown C version \"C95\""))
+        );
+        assert_eq!(
+            normalize(&[standard("C", "C99", Some("XSI"))]).err(),
+            Some(String::from("unknown C option group \"XSI\""))
+        );
+    }
+
+    #[test]
+    fn filter_lets_through_older_versions_of_the_same_standard() {
+        let c11 = StandardFilter::parse("C11").unwrap();
+
+        assert!(c11.matches(&[standard("C", "C99", None)]));
+        assert!(c11.matches(&[standard("C", "C11", None)]));
+        assert!(!c11.matches(&[standard("C", "C17", None)]));
+        assert!(!c11.matches(&[standard("POSIX", "POSIX.1-2001", None)]));
+        assert_eq!(c11.to_string(), "C11");
+    }
+
+    #[test]
+    fn filter_option_groups_must_be_requested() {
+        let xsi = [standard("POSIX", "POSIX.1-2008", Some("XSI"))];
+
+        assert!(!StandardFilter::parse("POSIX.1-2008").unwrap().matches(&xsi));
+        assert!(StandardFilter::parse("2017+xsi").unwrap().matches(&xsi));
+        assert!(StandardFilter::parse("POSIX").unwrap().matches(&[standard(
+            "POSIX",
+            "POSIX.1-2024",
+            None
+        )]));
+        assert_eq!(
+            StandardFilter::parse("2017+xsi").unwrap().to_string(),
+            "POSIX.1-2017+XSI"
+        );
+        assert!(StandardFilter::parse("POSIX.1-2008+NOPE").is_err());
+        assert!(StandardFilter::parse("C2049").is_err());
+    }
+}
@@ -0,0 +1,259 @@
+//! The canonical names `os_affinity`, `arch_affinity` and `availability`
+//! platforms may use.
+//!
+//! A corpus can define its own lists in an `os.yaml`, an `arch.yaml` and a
+//! `platform.yaml` at its root:
+//!
+//! ```yaml
+//! - name: linux
+//!   aliases: [gnu/linux]
+//! - name: macos
+//!   aliases: [darwin, osx]
+//! ```
+//!
+//! The order of a list is the canonical order affinities are written in.
+//! Names and aliases match case-insensitively.
+
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+pub(crate) const OS_VOCABULARY_FILE: &str = "os.yaml";
+pub(crate) const ARCH_VOCABULARY_FILE: &str = "arch.yaml";
+pub(crate) const PLATFORM_VOCABULARY_FILE: &str = "platform.yaml";
+
+const DEFAULT_OS_VOCABULARY: &[(&str, &[&str])] = &[
+    ("linux", &["gnu/linux"]),
+    ("macos", &["darwin", "osx", "mac os x", "macosx"]),
+    ("windows", &["win32", "win64"]),
+    ("freebsd", &[]),
+    ("netbsd", &[]),
+    ("openbsd", &[]),
+];
+
+const DEFAULT_ARCH_VOCABULARY: &[(&str, &[&str])] = &[
+    ("x86_64", &["amd64", "x64", "x86-64"]),
+    ("i386", &["x86", "i686", "ia32"]),
+    ("aarch64", &["arm64"]),
+    ("arm", &["armv7", "aarch32"]),
+    ("riscv64", &[]),
+    ("powerpc64", &["ppc64", "ppc64le"]),
+    ("s390x", &[]),
+];
+
+/// The C libraries and OS releases `availability` versions are given for.
+const DEFAULT_PLATFORM_VOCABULARY: &[(&str, &[&str])] = &[
+    ("glibc", &["gnu libc"]),
+    ("musl", &[]),
+    ("bionic", &["android"]),
+    ("linux", &["kernel"]),
+    ("macos", &["darwin", "osx", "mac os x", "macosx"]),
+    ("ios", &[]),
+    ("windows", &["win32", "win64"]),
+    ("freebsd", &[]),
+    ("netbsd", &[]),
+    ("openbsd", &[]),
+];
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct Term {
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+pub(crate) struct Vocabulary {
+    /// What the terms name, for messages (`unknown OS "plan9"`).
+    pub noun: &'static str,
+    pub terms: Vec<Term>,
+}
+
+impl Vocabulary {
+    /// Reads `os.yaml` from the corpus root, falling back to the built-in
+    /// list when there is none.
+    pub fn load_os(filepath: &str) -> Vocabulary {
+        Vocabulary::load(filepath, OS_VOCABULARY_FILE, "OS", DEFAULT_OS_VOCABULARY)
+    }
+
+    /// Reads `arch.yaml` from the corpus root, falling back to the built-in
+    /// list when there is none.
+    pub fn load_arch(filepath: &str) -> Vocabulary {
+        Vocabulary::load(
+            filepath,
+            ARCH_VOCABULARY_FILE,
+            "architecture",
+            DEFAULT_ARCH_VOCABULARY,
+        )
+    }
+
+    /// Reads `platform.yaml` from the corpus root, falling back to the
+    /// built-in list when there is none.
+    pub fn load_platform(filepath: &str) -> Vocabulary {
+        Vocabulary::load(
+            filepath,
+            PLATFORM_VOCABULARY_FILE,
+            "platform",
+            DEFAULT_PLATFORM_VOCABULARY,
+        )
+    }
+
+    fn load(
+        filepath: &str,
+        file_name: &str,
+        noun: &'static str,
+        default: &[(&str, &[&str])],
+    ) -> Vocabulary {
+        let path = Path::new(filepath).join(file_name);
+        let default_terms = || {
+            default
+                .iter()
+                .map(|(name, aliases)| Term {
+                    name: name.to_string(),
+                    aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+                })
+                .collect()
+        };
+
+        if !path.is_file() {
+            return Vocabulary {
+                noun,
+                terms: default_terms(),
+            };
+        }
+
+        let terms = fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|contents| {
+                serde_yaml::from_str::<Vec<Term>>(&contents).map_err(|error| error.to_string())
+            });
+
+        match terms {
+            Ok(terms) => Vocabulary { noun, terms },
+            Err(error) => {
+                eprintln!("Vocabulary Error ({:?}): {}", path, error);
+                Vocabulary {
+                    noun,
+                    terms: default_terms(),
+                }
+            }
+        }
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.terms.iter().map(|term| term.name.as_str()).collect()
+    }
+
+    /// The canonical name `value` refers to, if any.
+    pub fn canonical(&self, value: &str) -> Option<&str> {
+        let value = value.trim().to_lowercase();
+
+        self.terms
+            .iter()
+            .find(|term| {
+                term.name.to_lowercase() == value
+                    || term
+                        .aliases
+                        .iter()
+                        .any(|alias| alias.to_lowercase() == value)
+            })
+            .map(|term| term.name.as_str())
+    }
+
+    /// Maps every value to its canonical name, dropping duplicates and
+    /// putting them in canonical order. Fails on the first unknown value.
+    pub fn normalize(&self, values: &[String]) -> Result<Vec<String>, String> {
+        let mut normalized = Vec::new();
+
+        for value in values.iter() {
+            match self.canonical(value) {
+                Some(name) => {
+                    if !normalized.iter().any(|n: &String| n == name) {
+                        normalized.push(name.to_string());
+                    }
+                }
+                None => return Err(format!("unknown {} \"{}\"", self.noun, value)),
+            }
+        }
+
+        self.sort(&mut normalized);
+
+        Ok(normalized)
+    }
+
+    /// Sorts canonical names into canonical order.
+    pub fn sort(&self, values: &mut [String]) {
+        values.sort_by_key(|value| {
+            self.terms
+                .iter()
+                .position(|term| &term.name == value)
+                .unwrap_or(usize::MAX)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn aliases_resolve_case_insensitively() {
+        let os = Vocabulary::load_os("/nonexistent");
+        let arch = Vocabulary::load_arch("/nonexistent");
+
+        assert_eq!(os.canonical("Darwin"), Some("macos"));
+        assert_eq!(os.canonical(" GNU/Linux "), Some("linux"));
+        assert_eq!(arch.canonical("AMD64"), Some("x86_64"));
+        assert_eq!(arch.canonical("arm64"), Some("aarch64"));
+        assert_eq!(os.canonical("plan9"), None);
+    }
+
+    #[test]
+    fn normalize_dedups_and_sorts_canonically() {
+        let os = Vocabulary::load_os("/nonexistent");
+
+        assert_eq!(
+            os.normalize(&strings(&["win32", "osx", "linux", "macos"])),
+            Ok(strings(&["linux", "macos", "windows"]))
+        );
+    }
+
+    #[test]
+    fn normalize_rejects_unknown_names() {
+        let os = Vocabulary::load_os("/nonexistent");
+
+        assert_eq!(
+            os.normalize(&strings(&["linux", "plan9"])),
+            Err(String::from("unknown OS \"plan9\""))
+        );
+
+        let arch = Vocabulary::load_arch("/nonexistent");
+
+        assert_eq!(
+            arch.normalize(&strings(&["x86_64", "vax"])),
+            Err(String::from("unknown architecture \"vax\""))
+        );
+    }
+
+    #[test]
+    fn corpus_vocabulary_replaces_the_default() {
+        let root = crate::fixtures::temp_dir("vocabulary");
+        fs::write(
+            root.join(OS_VOCABULARY_FILE),
+            "- name: plan9\n  aliases: [p9]\n- name: linux\n",
+        )
+        .unwrap();
+
+        let os = Vocabulary::load_os(root.to_str().unwrap());
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(os.names(), vec!["plan9", "linux"]);
+        assert_eq!(
+            os.normalize(&strings(&["linux", "P9"])),
+            Ok(strings(&["plan9", "linux"]))
+        );
+        assert_eq!(os.canonical("macos"), None);
+    }
+}